@@ -0,0 +1,54 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use crypto_bigint::U8192;
+use miller_rabin::{baillie_psw, miller_rabin, miller_rabin_uint};
+
+// 2^bits - 1 isn't prime for these sizes: the test stops after its first round (there are very
+// few bases it passes for), which makes U8192 affordable to compare against
+fn composite(bits: usize) -> Vec<u8> {
+    vec![0xff; bits / 8]
+}
+
+fn padded_u8192(bytes: &[u8]) -> U8192 {
+    let mut padded = [0u8; 1024];
+    padded[1024 - bytes.len()..].copy_from_slice(bytes);
+    U8192::from_be_slice(&padded)
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Miller-Rabin on 2^k - 1");
+    group.sample_size(10);
+    for bits in [256, 1024, 2048] {
+        let bytes = composite(bits);
+        group.bench_with_input(BenchmarkId::new("smallest width", bits), &bytes, |b, n| {
+            b.iter(|| miller_rabin(n).unwrap())
+        });
+        let n = padded_u8192(&bytes);
+        group.bench_with_input(BenchmarkId::new("U8192", bits), &n, |b, n| {
+            b.iter(|| miller_rabin_uint(n).unwrap())
+        });
+    }
+    group.finish();
+
+    // 128 rounds on a prime
+    let secp256k1_prime =
+        hex::decode("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f").unwrap();
+    c.bench_function("Miller-Rabin on the secp256k1 prime", |b| {
+        b.iter(|| miller_rabin(&secp256k1_prime).unwrap())
+    });
+
+    // 2^2048 - 1557, the largest 2048-bit prime: the size of an RSA modulus
+    let mut rsa_size_prime = vec![0xff; 256];
+    rsa_size_prime[254..].copy_from_slice(&[0xf9, 0xeb]);
+    let mut group = c.benchmark_group("Miller-Rabin on a 2048-bit prime");
+    group.sample_size(10);
+    group.bench_function("128 rounds", |b| {
+        b.iter(|| miller_rabin(&rsa_size_prime).unwrap())
+    });
+    group.bench_function("Baillie-PSW", |b| {
+        b.iter(|| baillie_psw(&rsa_size_prime).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
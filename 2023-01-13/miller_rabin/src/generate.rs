@@ -0,0 +1,357 @@
+//! Random primes of a given bit size: plain primes, safe primes p = 2q + 1 (with q prime, a
+//! Sophie Germain prime), and Schnorr groups p = kq + 1.
+//!
+//! Candidates are searched incrementally, like OpenSSL does: pick a random odd start with its top
+//! two bits set, then walk up from it. The remainders of the start by every small prime are
+//! computed once, so that ruling out a candidate divisible by one of them is a few word operations
+//! instead of a Miller-Rabin round. About 85% of odd candidates don't survive the sieve.
+//!
+//! Setting the top two bits means that the product of two `bits`-bit primes has exactly 2.`bits`
+//! bits, as RSA wants.
+
+use crypto_bigint::{Limb, Random, Uint, WideWord, Word};
+use rand_core::CryptoRngCore;
+
+use crate::{miller_rabin_uint_with_rng, pow_mod, MillerRabinError, ROUNDS};
+
+// Sieve with the primes below this
+const SIEVE_LIMIT: u32 = 2048;
+// How far to walk up from a random start before picking a new one
+const MAX_DELTA: u64 = 1 << 20;
+// Below that, safe primes and Schnorr groups with the top two bits set get scarce, or don't exist
+// at all: there's no 4-bit or 5-bit safe prime with its top two bits set.
+const MIN_SAFE_PRIME_BITS: usize = 16;
+
+/// A prime p, a prime q dividing p - 1, and g generating the subgroup of order q of (Z/pZ)*.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SchnorrGroup<const LIMBS: usize> {
+    pub p: Uint<LIMBS>,
+    pub q: Uint<LIMBS>,
+    pub g: Uint<LIMBS>,
+}
+
+/// A random prime of exactly `bits` bits, with its top two bits set. `rng` picks the candidates
+/// and the Miller-Rabin bases: with a seeded `rng`, the same prime comes out every time.
+/// Errors if `bits` is below 2, or doesn't fit in a `Uint<LIMBS>`.
+pub fn gen_prime<const LIMBS: usize>(
+    bits: usize,
+    rng: &mut impl CryptoRngCore,
+) -> Result<Uint<LIMBS>, MillerRabinError> {
+    check_bit_size::<LIMBS>(bits, 2)?;
+    let sieve = Sieve::new(bits, false);
+    loop {
+        if let Some(p) = sieve.search(rng, is_prime) {
+            return Ok(p);
+        }
+    }
+}
+
+/// A random safe prime p of exactly `bits` bits, with its top two bits set: (p - 1) / 2 is prime
+/// too. Errors if `bits` is below 16, or doesn't fit in a `Uint<LIMBS>`.
+pub fn gen_safe_prime<const LIMBS: usize>(
+    bits: usize,
+    rng: &mut impl CryptoRngCore,
+) -> Result<Uint<LIMBS>, MillerRabinError> {
+    check_bit_size::<LIMBS>(bits, MIN_SAFE_PRIME_BITS)?;
+    // p has its top two bits set iff q does
+    let (_, p) = sophie_germain_pair(bits - 1, rng);
+    Ok(p)
+}
+
+/// A random Sophie Germain prime q of exactly `bits` bits, with its top two bits set, along with
+/// the safe prime 2q + 1. Errors if `bits` is below 16, or if 2q + 1 doesn't fit in a
+/// `Uint<LIMBS>`.
+pub fn gen_sophie_germain_prime<const LIMBS: usize>(
+    bits: usize,
+    rng: &mut impl CryptoRngCore,
+) -> Result<(Uint<LIMBS>, Uint<LIMBS>), MillerRabinError> {
+    check_bit_size::<LIMBS>(bits + 1, MIN_SAFE_PRIME_BITS + 1)?;
+    Ok(sophie_germain_pair(bits, rng))
+}
+
+/// Parameters for a Schnorr group: a `q_bits`-bit prime q, a `p_bits`-bit prime p = kq + 1, and
+/// a generator g = h^k (mod p) of the subgroup of order q, for the smallest h >= 2 that gives
+/// g != 1. Errors if `q_bits` is below 16, if p doesn't fit in a `Uint<LIMBS>`, or if q wouldn't
+/// be smaller than p.
+pub fn gen_schnorr_group<const LIMBS: usize>(
+    p_bits: usize,
+    q_bits: usize,
+    rng: &mut impl CryptoRngCore,
+) -> Result<SchnorrGroup<LIMBS>, MillerRabinError> {
+    check_bit_size::<LIMBS>(p_bits, MIN_SAFE_PRIME_BITS + 1)?;
+    if q_bits < MIN_SAFE_PRIME_BITS || q_bits >= p_bits {
+        return Err(MillerRabinError::InvalidSubgroupSize(q_bits, p_bits));
+    }
+
+    let p_top_bits = Uint::<LIMBS>::from(3u8).shl_vartime(p_bits - 2);
+    loop {
+        let q = gen_prime::<LIMBS>(q_bits, rng)?;
+        let two_q = q.shl_vartime(1);
+        // About one in ln(p) / 2 candidates is prime: if this q isn't lucky after many more than
+        // that, maybe no p = kq + 1 of the right size is prime, try another one
+        for _ in 0..4 * p_bits {
+            // Round a random p_bits-bit number down to a multiple of 2q, and add 1
+            let x = random_bits::<LIMBS>(p_bits, rng).bitor(&p_top_bits);
+            let p = x
+                .wrapping_sub(&x.wrapping_rem(&two_q))
+                .wrapping_add(&Uint::ONE);
+            if p.bits_vartime() != p_bits || !is_prime(&p, rng) {
+                continue;
+            }
+            let k = p.wrapping_sub(&Uint::ONE).checked_div(&q).unwrap();
+            let mut h = Uint::from(2u8);
+            loop {
+                let g = pow_mod(&h, &k, &p)?;
+                if g != Uint::ONE {
+                    return Ok(SchnorrGroup { p, q, g });
+                }
+                h = h.wrapping_add(&Uint::ONE);
+            }
+        }
+    }
+}
+
+// (q, 2q + 1), both prime, with q of `bits` bits and its top two bits set
+fn sophie_germain_pair<const LIMBS: usize>(
+    bits: usize,
+    rng: &mut impl CryptoRngCore,
+) -> (Uint<LIMBS>, Uint<LIMBS>) {
+    let sieve = Sieve::new(bits, true);
+    loop {
+        // p is only tested once q is known to be prime
+        if let Some(q) = sieve.search(rng, |q, rng| {
+            is_prime(q, rng) && is_prime(&safe_prime(q), rng)
+        }) {
+            return (q, safe_prime(&q));
+        }
+    }
+}
+
+fn safe_prime<const LIMBS: usize>(q: &Uint<LIMBS>) -> Uint<LIMBS> {
+    q.shl_vartime(1).wrapping_add(&Uint::ONE)
+}
+
+struct Sieve {
+    bits: usize,
+    // Also rule out candidates q for which 2q + 1 is divisible by a small prime
+    safe: bool,
+    primes: Vec<u32>,
+}
+
+impl Sieve {
+    fn new(bits: usize, safe: bool) -> Self {
+        // Candidates are >= 2^(bits - 1): a small prime below that which divides one of them
+        // (or 2q + 1, which is even larger) can't be equal to it
+        let limit = 1u32
+            .checked_shl(bits as u32 - 1)
+            .map_or(SIEVE_LIMIT, |lowest| lowest.min(SIEVE_LIMIT));
+        Self {
+            bits,
+            safe,
+            primes: small_primes(limit),
+        }
+    }
+
+    // Walks up from a random start, and returns the first candidate which passes the sieve and
+    // `is_wanted`. None if it runs out of candidates of the right size, or past MAX_DELTA.
+    fn search<const LIMBS: usize, R: CryptoRngCore>(
+        &self,
+        rng: &mut R,
+        is_wanted: impl Fn(&Uint<LIMBS>, &mut R) -> bool,
+    ) -> Option<Uint<LIMBS>> {
+        let top_bits = Uint::<LIMBS>::from(3u8).shl_vartime(self.bits - 2);
+        let start = random_bits::<LIMBS>(self.bits, rng)
+            .bitor(&top_bits)
+            .bitor(&Uint::ONE);
+        let remainders: Vec<u64> = self
+            .primes
+            .iter()
+            .map(|&r| rem_small(&start, r) as u64)
+            .collect();
+
+        'candidates: for delta in (0..MAX_DELTA).step_by(2) {
+            for (&r, &rem) in self.primes.iter().zip(&remainders) {
+                let (r, rem) = (r as u64, (rem + delta) % r as u64);
+                if rem == 0 || (self.safe && (2 * rem + 1) % r == 0) {
+                    continue 'candidates;
+                }
+            }
+            let candidate = start.wrapping_add(&Uint::from(delta));
+            if candidate.bits_vartime() != self.bits {
+                // Walked past 2^bits
+                return None;
+            }
+            if is_wanted(&candidate, rng) {
+                return Some(candidate);
+            }
+        }
+        None
+    }
+}
+
+fn check_bit_size<const LIMBS: usize>(bits: usize, min: usize) -> Result<(), MillerRabinError> {
+    let max = LIMBS * Limb::BITS;
+    if bits < min || bits > max {
+        return Err(MillerRabinError::InvalidBitSize(bits, min, max));
+    }
+    Ok(())
+}
+
+fn is_prime<const LIMBS: usize>(n: &Uint<LIMBS>, rng: &mut impl CryptoRngCore) -> bool {
+    match miller_rabin_uint_with_rng(n, ROUNDS, rng) {
+        Ok(is_prime) => is_prime,
+        // Even, or <= 3
+        Err(_) => *n == Uint::from(2u8) || *n == Uint::from(3u8),
+    }
+}
+
+// A uniformly random number of at most `bits` bits
+fn random_bits<const LIMBS: usize>(bits: usize, rng: &mut impl CryptoRngCore) -> Uint<LIMBS> {
+    Uint::<LIMBS>::random(rng) >> (LIMBS * Limb::BITS - bits)
+}
+
+// n mod r, with Horner's method on the limbs, from the top
+pub(crate) fn rem_small<const LIMBS: usize>(n: &Uint<LIMBS>, r: u32) -> u32 {
+    n.as_words().iter().rev().fold(0, |rem, &word| {
+        (((rem as WideWord) << Word::BITS | word as WideWord) % r as WideWord) as u32
+    })
+}
+
+// The primes < limit, with the sieve of Eratosthenes
+fn small_primes(limit: u32) -> Vec<u32> {
+    let mut is_composite = vec![false; limit as usize];
+    let mut primes = vec![];
+    for n in 2..limit {
+        if is_composite[n as usize] {
+            continue;
+        }
+        primes.push(n);
+        for multiple in (n * n..limit).step_by(n as usize) {
+            is_composite[multiple as usize] = true;
+        }
+    }
+    primes
+}
+
+#[cfg(test)]
+mod test {
+    use crypto_bigint::{Random, Uint, U1024, U128, U256, U64};
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    use crate::generate::{
+        gen_prime, gen_safe_prime, gen_schnorr_group, gen_sophie_germain_prime, rem_small,
+        small_primes,
+    };
+    use crate::{baillie_psw_uint, pow_mod};
+
+    fn has_top_two_bits<const LIMBS: usize>(n: &Uint<LIMBS>, bits: usize) -> bool {
+        n.bits_vartime() == bits && n.bit_vartime(bits - 2)
+    }
+
+    #[test]
+    fn test_small_primes() {
+        let mut rng = ChaCha20Rng::seed_from_u64(2039);
+        assert_eq!(small_primes(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(small_primes(2048).len(), 309);
+
+        let n = U256::random(&mut rng);
+        let expected = n.wrapping_rem(&U256::from(2039u32));
+        assert_eq!(U256::from(rem_small(&n, 2039)), expected);
+    }
+
+    #[test]
+    fn test_gen_prime() {
+        let mut rng = ChaCha20Rng::seed_from_u64(1);
+        // The only 2-bit and 3-bit primes with their top two bits set
+        assert_eq!(
+            gen_prime::<{ U64::LIMBS }>(2, &mut rng).unwrap(),
+            U64::from(3u8)
+        );
+        assert_eq!(
+            gen_prime::<{ U64::LIMBS }>(3, &mut rng).unwrap(),
+            U64::from(7u8)
+        );
+        // 4 bits: 13, the sieve must not rule out small primes
+        assert_eq!(
+            gen_prime::<{ U64::LIMBS }>(4, &mut rng).unwrap(),
+            U64::from(13u8)
+        );
+
+        for bits in [5, 12, 64, 200, 256] {
+            let p = gen_prime::<{ U256::LIMBS }>(bits, &mut rng).unwrap();
+            assert!(has_top_two_bits(&p, bits));
+            assert!(baillie_psw_uint(&p).unwrap());
+        }
+
+        assert_eq!(
+            gen_prime::<{ U64::LIMBS }>(65, &mut rng)
+                .unwrap_err()
+                .to_string(),
+            "Cannot generate a 65-bit prime: the size must be between 2 and 64 bits".to_string()
+        );
+        assert!(gen_prime::<{ U64::LIMBS }>(1, &mut rng).is_err());
+
+        // Seeded, the search is reproducible
+        let p = gen_prime::<{ U256::LIMBS }>(256, &mut ChaCha20Rng::seed_from_u64(7919)).unwrap();
+        let q = gen_prime::<{ U256::LIMBS }>(256, &mut ChaCha20Rng::seed_from_u64(7919)).unwrap();
+        assert_eq!(p, q);
+    }
+
+    #[test]
+    fn test_gen_safe_prime() {
+        let mut rng = ChaCha20Rng::seed_from_u64(2);
+        for bits in [16, 64, 128] {
+            let p = gen_safe_prime::<{ U128::LIMBS }>(bits, &mut rng).unwrap();
+            assert!(has_top_two_bits(&p, bits));
+            assert!(baillie_psw_uint(&p).unwrap());
+            assert!(baillie_psw_uint(&(p >> 1)).unwrap());
+        }
+        assert!(gen_safe_prime::<{ U128::LIMBS }>(15, &mut rng).is_err());
+        assert!(gen_safe_prime::<{ U128::LIMBS }>(129, &mut rng).is_err());
+
+        let (q, p) = gen_sophie_germain_prime::<{ U128::LIMBS }>(127, &mut rng).unwrap();
+        assert!(has_top_two_bits(&q, 127));
+        assert_eq!(p, (q << 1).wrapping_add(&U128::ONE));
+        assert!(baillie_psw_uint(&q).unwrap());
+        assert!(baillie_psw_uint(&p).unwrap());
+        // 2q + 1 has 129 bits
+        assert!(gen_sophie_germain_prime::<{ U128::LIMBS }>(128, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_readme_safe_prime() {
+        let p = U1024::from_be_hex(concat!(
+            "C641901063D1505D50AC7F8EB82D46B4FCA8AF62E7CF365473030ADB1F9B34E4",
+            "D7D3F3C18EE0008BFECB7E646548F5A8940A439E44160DC8F608662EB9516012",
+            "120C71F0F969AF5FE225C73D1CF4A1288C1DAD564E97270AFF9BB91E6143D4E8",
+            "96098FC8B53E9E39820E4EAA5E4BC942B64FB24818EAFF362A913595EE475B9B",
+        ));
+        assert!(has_top_two_bits(&p, 1024));
+        assert!(baillie_psw_uint(&p).unwrap());
+        assert!(baillie_psw_uint(&(p >> 1)).unwrap());
+    }
+
+    #[test]
+    fn test_gen_schnorr_group() {
+        let mut rng = ChaCha20Rng::seed_from_u64(3);
+        let group = gen_schnorr_group::<{ U1024::LIMBS }>(1024, 160, &mut rng).unwrap();
+        assert_eq!(group.p.bits_vartime(), 1024);
+        assert!(has_top_two_bits(&group.q, 160));
+        assert!(baillie_psw_uint(&group.p).unwrap());
+        assert!(baillie_psw_uint(&group.q).unwrap());
+        // q divides p - 1, and g has order q
+        let p_minus_one = group.p.wrapping_sub(&U1024::ONE);
+        assert_eq!(p_minus_one.wrapping_rem(&group.q), U1024::ZERO);
+        assert_ne!(group.g, U1024::ONE);
+        assert_eq!(pow_mod(&group.g, &group.q, &group.p).unwrap(), U1024::ONE);
+
+        assert_eq!(
+            gen_schnorr_group::<{ U1024::LIMBS }>(1024, 1024, &mut rng)
+                .unwrap_err()
+                .to_string(),
+            "Cannot generate a Schnorr group with a 1024-bit q and a 1024-bit p".to_string()
+        );
+        assert!(gen_schnorr_group::<{ U1024::LIMBS }>(64, 8, &mut rng).is_err());
+    }
+}
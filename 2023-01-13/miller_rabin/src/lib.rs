@@ -1,7 +1,20 @@
-use crypto_bigint::{CheckedSub, Integer, Random, U8192};
-use rand_core::OsRng;
+pub use bpsw::{baillie_psw_uint, strong_lucas_probable_prime};
+use crypto_bigint::modular::runtime_mod::{DynResidue, DynResidueParams};
+use crypto_bigint::{
+    CheckedSub, Integer, Limb, Random, Uint, Word, U1024, U128, U2048, U256, U4096, U512, U64,
+    U8192,
+};
+pub use generate::{
+    gen_prime, gen_safe_prime, gen_schnorr_group, gen_sophie_germain_prime, SchnorrGroup,
+};
+use montgomery::residue_params;
+use rand_core::{CryptoRngCore, OsRng};
 use thiserror::Error;
 
+pub mod bpsw;
+pub mod generate;
+pub mod montgomery;
+
 #[derive(Error, Debug)]
 pub enum MillerRabinError {
     #[error("This function only supports up to 1,024 bytes of input. Got {0}.")]
@@ -16,88 +29,261 @@ pub enum MillerRabinError {
     MulModOperandTooHigh,
     #[error("Exponentiation modulo p requires operand to be < p")]
     PowMulOperandTooHigh,
+    #[error("Cannot generate a {0}-bit prime: the size must be between {1} and {2} bits")]
+    InvalidBitSize(usize, usize, usize),
+    #[error("Cannot generate a Schnorr group with a {0}-bit q and a {1}-bit p")]
+    InvalidSubgroupSize(usize, usize),
+    #[error("A strong probable prime test to base a requires 2 <= a <= n - 2")]
+    InvalidBase,
+    #[error("Deterministic Miller-Rabin is only proven for n < 3,317,044,064,679,887,385,961,981")]
+    TooLargeForDeterministic,
+}
+
+// Runs `$body` with `$n` bound to big-endian `$bytes` (without leading zeros) as the smallest of
+// U64, U128...U8192 it fits in
+macro_rules! with_smallest_uint {
+    ($bytes:expr, $n:ident => $body:expr) => {
+        match $bytes.len() {
+            0..=8 => {
+                let $n = from_be_bytes::<{ U64::LIMBS }>($bytes);
+                $body
+            }
+            9..=16 => {
+                let $n = from_be_bytes::<{ U128::LIMBS }>($bytes);
+                $body
+            }
+            17..=32 => {
+                let $n = from_be_bytes::<{ U256::LIMBS }>($bytes);
+                $body
+            }
+            33..=64 => {
+                let $n = from_be_bytes::<{ U512::LIMBS }>($bytes);
+                $body
+            }
+            65..=128 => {
+                let $n = from_be_bytes::<{ U1024::LIMBS }>($bytes);
+                $body
+            }
+            129..=256 => {
+                let $n = from_be_bytes::<{ U2048::LIMBS }>($bytes);
+                $body
+            }
+            257..=512 => {
+                let $n = from_be_bytes::<{ U4096::LIMBS }>($bytes);
+                $body
+            }
+            _ => {
+                let $n = from_be_bytes::<{ U8192::LIMBS }>($bytes);
+                $body
+            }
+        }
+    };
 }
 
 const REQUIRED_BYTE_LENGTH_FOR_U8192: usize = 1024;
 const MAX_RANDOM_RETRIES: usize = 100;
+// Each round lets a composite through with probability at most 1/4
+const ROUNDS: usize = 128;
+
+// Testing the first k primes as bases is enough for all n below these bounds (Jaeschke 1993,
+// Jiang and Deng 2014, Sorenson and Webster 2015). Each bound is the smallest composite that
+// passes all of the bases: a strong pseudoprime to each of them.
+const DETERMINISTIC_BASES: [(u128, &[u8]); 10] = [
+    (2_047, &[2]),
+    (1_373_653, &[2, 3]),
+    (25_326_001, &[2, 3, 5]),
+    (3_215_031_751, &[2, 3, 5, 7]),
+    (2_152_302_898_747, &[2, 3, 5, 7, 11]),
+    (3_474_749_660_383, &[2, 3, 5, 7, 11, 13]),
+    (341_550_071_728_321, &[2, 3, 5, 7, 11, 13, 17]),
+    (3_825_123_056_546_413_051, &[2, 3, 5, 7, 11, 13, 17, 19, 23]),
+    (
+        318_665_857_834_031_151_167_461,
+        &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37],
+    ),
+    (
+        3_317_044_064_679_887_385_961_981,
+        &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41],
+    ),
+];
 
 /// Implementation of Miller-Rabbin primality test based on the description
 /// of the algorithm in Cryptography Engineering (Ferguson, Schneier, Kohno).
 /// This function expects bytes in Big-Endian order.
-/// Under the hood, we use crypto-bigint's U8192.
+/// Under the hood, we use the smallest of crypto-bigint's U64, U128...U8192 that fits `bytes`
+/// (leading zeros don't count): arithmetic on a 256-bit number is done in a U256.
 /// Hence this function errors if `bytes` has more than 1,024 u8 elements.
+/// Bases are picked with `OsRng`, for 128 rounds.
 pub fn miller_rabin(bytes: &[u8]) -> Result<bool, MillerRabinError> {
-    if bytes.len() > REQUIRED_BYTE_LENGTH_FOR_U8192 {
-        Err(MillerRabinError::TooManyBytes(bytes.len()))?;
-    }
+    miller_rabin_with_rng(bytes, ROUNDS, &mut OsRng)
+}
 
-    // Pad `bytes`, which may or may not be shorter than 1,024 bytes
-    let padded: [u8; REQUIRED_BYTE_LENGTH_FOR_U8192] = {
-        let mut padded = [0u8; REQUIRED_BYTE_LENGTH_FOR_U8192];
-        padded[REQUIRED_BYTE_LENGTH_FOR_U8192 - bytes.len()..].copy_from_slice(bytes);
-        padded
-    };
+/// Same as `miller_rabin`, with `rounds` random bases picked with `rng`: with a seeded `rng`, runs
+/// are reproducible. A composite passes with probability at most 4^-rounds.
+pub fn miller_rabin_with_rng(
+    bytes: &[u8],
+    rounds: usize,
+    rng: &mut impl CryptoRngCore,
+) -> Result<bool, MillerRabinError> {
+    let bytes = significant_bytes(bytes)?;
+    with_smallest_uint!(bytes, n => miller_rabin_uint_with_rng(&n, rounds, rng))
+}
 
-    let n = U8192::from_be_slice(&padded);
-    if n <= U8192::from(3u8) {
-        return Err(MillerRabinError::LowInteger);
+/// Same as `miller_rabin`, on a number that's already a crypto-bigint integer, of any size.
+/// All the arithmetic happens at that size.
+pub fn miller_rabin_uint<const LIMBS: usize>(n: &Uint<LIMBS>) -> Result<bool, MillerRabinError> {
+    miller_rabin_uint_with_rng(n, ROUNDS, &mut OsRng)
+}
+
+/// Same as `miller_rabin_with_rng`, on a crypto-bigint integer of any size.
+pub fn miller_rabin_uint_with_rng<const LIMBS: usize>(
+    n: &Uint<LIMBS>,
+    rounds: usize,
+    rng: &mut impl CryptoRngCore,
+) -> Result<bool, MillerRabinError> {
+    let test = StrongTest::new(n)?;
+    let limit = test.n_minus_one;
+    let bit_size = bit_len(n);
+    for _ in 0..rounds {
+        if !test.passes(&get_random(bit_size, limit, rng)?) {
+            return Ok(false);
+        }
     }
-    if bool::from(n.is_even()) {
-        return Err(MillerRabinError::EvenInteger);
+    Ok(true)
+}
+
+/// Deterministic Miller-Rabin: the bases are the first primes, as many as it takes for the
+/// answer to be proven right for this n (see `DETERMINISTIC_BASES`). That's 13 bases at most.
+/// Errors if n >= 3,317,044,064,679,887,385,961,981, about 2^81.5, where no such set is known.
+/// Same input conventions as `miller_rabin`.
+pub fn miller_rabin_deterministic(bytes: &[u8]) -> Result<bool, MillerRabinError> {
+    let bytes = significant_bytes(bytes)?;
+    if bytes.len() > 16 {
+        return Err(MillerRabinError::TooLargeForDeterministic);
     }
+    miller_rabin_uint_deterministic(&from_be_bytes::<{ U128::LIMBS }>(bytes))
+}
 
-    // Compute (s, t) such that s is odd and s.2^t = n-1
-    // Safe to unwrap because n is > 3. No underflow risk here.
-    let n_minus_one = n.checked_sub(&U8192::ONE).unwrap();
-    let mut s = n_minus_one;
-    let mut t = 0;
-    let two = &U8192::from(2u8);
-    while bool::from(s.is_even()) {
-        s = s.checked_div(two).unwrap();
-        t += 1;
+/// Baillie-PSW: a strong probable prime test to base 2, then a strong Lucas probable prime test
+/// (see `bpsw`). Deterministic, and no composite is known to pass it.
+/// Same input conventions as `miller_rabin`.
+pub fn baillie_psw(bytes: &[u8]) -> Result<bool, MillerRabinError> {
+    let bytes = significant_bytes(bytes)?;
+    with_smallest_uint!(bytes, n => baillie_psw_uint(&n))
+}
+
+/// Same as `miller_rabin_deterministic`, on a crypto-bigint integer of any size.
+pub fn miller_rabin_uint_deterministic<const LIMBS: usize>(
+    n: &Uint<LIMBS>,
+) -> Result<bool, MillerRabinError> {
+    let test = StrongTest::new(n)?;
+    let small_n = to_u128(n).ok_or(MillerRabinError::TooLargeForDeterministic)?;
+    let (_, bases) = DETERMINISTIC_BASES
+        .iter()
+        .find(|(bound, _)| small_n < *bound)
+        .ok_or(MillerRabinError::TooLargeForDeterministic)?;
+    // n > the previous bound > every base, so all of them are valid
+    Ok(bases.iter().all(|&a| test.passes(&Uint::from(a))))
+}
+
+/// Whether n is a strong probable prime to base a: with n - 1 = s.2^t and s odd, either
+/// a^s = 1 (mod n), or a^(s.2^i) = -1 (mod n) for some 0 <= i < t. Primes pass for every base, odd
+/// composites for at most a quarter of them: those are strong pseudoprimes to base a.
+pub fn strong_probable_prime<const LIMBS: usize>(
+    n: &Uint<LIMBS>,
+    a: &Uint<LIMBS>,
+) -> Result<bool, MillerRabinError> {
+    let test = StrongTest::new(n)?;
+    if a < &Uint::from(2u8) || a >= &test.n_minus_one {
+        return Err(MillerRabinError::InvalidBase);
     }
+    Ok(test.passes(a))
+}
+
+// One Miller-Rabin round per base, with everything that only depends on n computed once
+struct StrongTest<const LIMBS: usize> {
+    params: DynResidueParams<LIMBS>,
+    n_minus_one: Uint<LIMBS>,
+    s: Uint<LIMBS>,
+    t: usize,
+    // 1 and n - 1, in Montgomery form
+    one: DynResidue<LIMBS>,
+    minus_one: DynResidue<LIMBS>,
+}
+
+impl<const LIMBS: usize> StrongTest<LIMBS> {
+    fn new(n: &Uint<LIMBS>) -> Result<Self, MillerRabinError> {
+        check_candidate(n)?;
+        let n = *n;
+
+        // Compute (s, t) such that s is odd and s.2^t = n-1
+        // Safe to unwrap because n is > 3. No underflow risk here.
+        let n_minus_one = n.checked_sub(&Uint::ONE).unwrap();
+        let mut s = n_minus_one;
+        let mut t = 0;
+        let two = &Uint::from(2u8);
+        while bool::from(s.is_even()) {
+            s = s.checked_div(two).unwrap();
+            t += 1;
+        }
 
-    let mut k = 0;
-    let limit = n_minus_one;
-    let bit_size = bit_len(&n);
-    while k < 128 {
-        let a = get_random(bit_size, limit)?;
-        k += 1;
+        // All the arithmetic mod n happens in Montgomery form: 1 and n - 1 too
+        let params = residue_params(&n).unwrap();
+        Ok(Self {
+            params,
+            n_minus_one,
+            s,
+            t,
+            one: DynResidue::one(params),
+            minus_one: DynResidue::new(&n_minus_one, params),
+        })
+    }
 
+    // For a < n
+    fn passes(&self, a: &Uint<LIMBS>) -> bool {
+        let a = DynResidue::new(a, self.params);
         // Compute a^s mod n
-        let mut v = pow_mod(&a, &s, &n)?;
-        if v == U8192::ONE {
+        let mut v = montgomery::pow(&a, &self.s);
+        if v == self.one {
             // If v is 1, we're good for this basis! Carry on...
-            continue;
-        } else {
-            // The sequence v, v^2, v^4, v^(2^t) must end in (n-1), 1.
-            let mut i = 0;
-            while v != n_minus_one {
-                if i == t - 1 {
-                    return Ok(false);
-                } else {
-                    v = mul_mod(&v, &v, &n)?;
-                    i += 1
-                }
+            return true;
+        }
+        // The sequence v, v^2, v^4, v^(2^t) must end in (n-1), 1.
+        let mut i = 0;
+        while v != self.minus_one {
+            if i == self.t - 1 {
+                return false;
             }
+            v = v.square();
+            i += 1
         }
+        true
     }
-    Ok(true)
 }
 
-// Computes n^k (mod p)
-// Errors when n >= p
-fn pow_mod(n: &U8192, k: &U8192, p: &U8192) -> Result<U8192, MillerRabinError> {
+/// Computes n^k (mod p)
+/// Errors when n >= p
+/// Odd moduli go through Montgomery form, with sliding-window exponentiation (see `montgomery`).
+/// Even ones fall back to square-and-multiply.
+pub fn pow_mod<const LIMBS: usize>(
+    n: &Uint<LIMBS>,
+    k: &Uint<LIMBS>,
+    p: &Uint<LIMBS>,
+) -> Result<Uint<LIMBS>, MillerRabinError> {
     if n >= p {
         return Err(MillerRabinError::PowMulOperandTooHigh);
     }
-    let mut res = U8192::ONE;
+    if let Some(params) = residue_params(p) {
+        return Ok(montgomery::pow(&DynResidue::new(n, params), k).retrieve());
+    }
+
+    let mut res = Uint::ONE;
     // Series: n, n^2, n^4, n^8...n^(2^k)
     let mut exp_n = *n;
     // Series: m, n/2, m/4, ...m/(2^k)
     let mut divided_k = *k;
 
-    while divided_k != U8192::ZERO {
+    while divided_k != Uint::ZERO {
         // If last bit is one (odd number), we multiply by exp_n
         if bool::from(divided_k.is_odd()) {
             res = mul_mod(&res, &exp_n, p)?;
@@ -108,20 +294,29 @@ fn pow_mod(n: &U8192, k: &U8192, p: &U8192) -> Result<U8192, MillerRabinError> {
     Ok(res)
 }
 
-// Computes n * m (mod p)
-// Errors when n >= p
-fn mul_mod(n: &U8192, m: &U8192, p: &U8192) -> Result<U8192, MillerRabinError> {
+/// Computes n * m (mod p)
+/// Errors when n >= p
+/// Odd moduli go through Montgomery form: (n.R) * (m.R) / R = n.m.R (mod p), where dividing by R is
+/// a Montgomery reduction. Even ones fall back to add-and-double.
+pub fn mul_mod<const LIMBS: usize>(
+    n: &Uint<LIMBS>,
+    m: &Uint<LIMBS>,
+    p: &Uint<LIMBS>,
+) -> Result<Uint<LIMBS>, MillerRabinError> {
     if n >= p {
         return Err(MillerRabinError::MulModOperandTooHigh);
     }
+    if let Some(params) = residue_params(p) {
+        return Ok((DynResidue::new(n, params) * DynResidue::new(m, params)).retrieve());
+    }
 
-    let mut res = U8192::ZERO;
+    let mut res = Uint::ZERO;
     // Series: n, 2n, 4n, 8n...(2^k)n
     let mut doubled_n = *n;
     // Series: m, n/2, m/4, ...m/(2^k)
     let mut divided_m = *m;
 
-    while divided_m != U8192::ZERO {
+    while divided_m != Uint::ZERO {
         // If last bit is one (odd number), we add "multiplied n"
         if bool::from(divided_m.is_odd()) {
             res = res.add_mod(&doubled_n, p);
@@ -133,20 +328,24 @@ fn mul_mod(n: &U8192, m: &U8192, p: &U8192) -> Result<U8192, MillerRabinError> {
 }
 
 /// Get a random number 2 <= n <= `limit`, of bit size `bit_size`.
-/// We start by generating a fully random U8192, then shift its bits right to get the proper bit size.
+/// We start by generating a fully random Uint, then shift its bits right to get the proper bit size.
 /// We then check for boundary conditions.
 /// If the boundary conditions aren't met, we loop and retry up to 100 times.
 ///
 /// /!\ This function is easy to misuse /!\
 /// if `bit_size` is set way higher than log2(limit), randomly picking an integer
 /// of `bit_size` length is unlikely to be within (2, limit) -- it'll usually be way higher!
-fn get_random(bit_size: usize, limit: U8192) -> Result<U8192, MillerRabinError> {
+fn get_random<const LIMBS: usize>(
+    bit_size: usize,
+    limit: Uint<LIMBS>,
+    rng: &mut impl CryptoRngCore,
+) -> Result<Uint<LIMBS>, MillerRabinError> {
     let mut retry_counter = 0;
 
     while retry_counter < MAX_RANDOM_RETRIES {
-        let mut num = U8192::random(OsRng);
-        num >>= 8192 - bit_size;
-        if num > U8192::ONE && num <= limit {
+        let mut num = Uint::<LIMBS>::random(&mut *rng);
+        num >>= LIMBS * Limb::BITS - bit_size;
+        if num > Uint::ONE && num <= limit {
             return Ok(num);
         }
         retry_counter += 1;
@@ -154,28 +353,65 @@ fn get_random(bit_size: usize, limit: U8192) -> Result<U8192, MillerRabinError>
     Err(MillerRabinError::CannotPickRandomBasis)
 }
 
-// Returns the number of non-zero bits of a U8192.
-// To do this we iterate with the right-shift operator until we reach 0.
-// There's probably a more performant way to do this, by e.g. iterating on limbs?
-fn bit_len(n: &U8192) -> usize {
-    let mut n = *n;
-    let mut bit_len = 0;
-    while bit_len < 8192 {
-        if n == U8192::ZERO {
-            return bit_len;
-        } else {
-            n >>= 1;
-            bit_len += 1
-        }
+// Returns the number of non-zero bits of a Uint.
+// crypto-bigint does it limb by limb: it skips the leading zero limbs, then counts the leading
+// zeros of the top one.
+fn bit_len<const LIMBS: usize>(n: &Uint<LIMBS>) -> usize {
+    n.bits_vartime()
+}
+
+// The tests here only take odd n > 3
+fn check_candidate<const LIMBS: usize>(n: &Uint<LIMBS>) -> Result<(), MillerRabinError> {
+    if n <= &Uint::from(3u8) {
+        return Err(MillerRabinError::LowInteger);
+    }
+    if bool::from(n.is_even()) {
+        return Err(MillerRabinError::EvenInteger);
+    }
+    Ok(())
+}
+
+// `bytes` without its leading zeros. Errors if there are more than 1,024 bytes, even zeros.
+fn significant_bytes(bytes: &[u8]) -> Result<&[u8], MillerRabinError> {
+    if bytes.len() > REQUIRED_BYTE_LENGTH_FOR_U8192 {
+        Err(MillerRabinError::TooManyBytes(bytes.len()))?;
+    }
+    let first_non_zero = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+    Ok(&bytes[first_non_zero..])
+}
+
+// n as a u128, if it fits
+fn to_u128<const LIMBS: usize>(n: &Uint<LIMBS>) -> Option<u128> {
+    if n.bits_vartime() > 128 {
+        return None;
     }
-    8192
+    Some(
+        n.as_words()
+            .iter()
+            .rev()
+            .fold(0, |acc, &word| (acc << Word::BITS) | word as u128),
+    )
+}
+
+// Left-pads big-endian `bytes` to the size of a Uint<LIMBS>. `bytes` must fit.
+fn from_be_bytes<const LIMBS: usize>(bytes: &[u8]) -> Uint<LIMBS> {
+    let mut padded = vec![0u8; LIMBS * Limb::BYTES];
+    padded[LIMBS * Limb::BYTES - bytes.len()..].copy_from_slice(bytes);
+    Uint::from_be_slice(&padded)
 }
 
 #[cfg(test)]
 mod test {
-    use crypto_bigint::{Checked, U8192};
+    use crypto_bigint::{Checked, U128, U256, U64, U8192};
+
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::{OsRng, SeedableRng};
 
-    use crate::{bit_len, get_random, miller_rabin, mul_mod, pow_mod};
+    use crate::{
+        bit_len, from_be_bytes, get_random, miller_rabin, miller_rabin_deterministic,
+        miller_rabin_uint, miller_rabin_uint_deterministic, miller_rabin_with_rng, mul_mod,
+        pow_mod, strong_probable_prime, to_u128, MillerRabinError,
+    };
 
     #[test]
     fn test_miller_rabin() {
@@ -199,6 +435,159 @@ mod test {
         assert!(miller_rabin(&ed25519_prime).unwrap());
     }
 
+    // 2^bits - c, big-endian
+    fn power_of_two_minus(bits: usize, c: u16) -> Vec<u8> {
+        let mut bytes = vec![0xff; bits / 8];
+        let low = u16::MAX - (c - 1);
+        bytes[bits / 8 - 2..].copy_from_slice(&low.to_be_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_rsa_size_miller_rabin() {
+        // A few seeded rounds: the 128-round timing is in the benchmarks
+        let mut rng = ChaCha20Rng::seed_from_u64(2048);
+        let mut test = |bytes: &[u8]| miller_rabin_with_rng(bytes, 4, &mut rng).unwrap();
+        // The largest 1024-bit and 2048-bit primes, and their neighbours
+        assert!(test(&power_of_two_minus(1024, 105)));
+        assert!(!test(&power_of_two_minus(1024, 107)));
+        assert!(test(&power_of_two_minus(2048, 1557)));
+        assert!(!test(&power_of_two_minus(2048, 1559)));
+        // 2^1279 - 1 is a Mersenne prime
+        let mut mersenne = vec![0xff; 160];
+        mersenne[0] = 0x7f;
+        assert!(test(&mersenne));
+    }
+
+    #[test]
+    fn test_miller_rabin_widths() {
+        // Leading zeros don't make the test any slower: this runs on a U64
+        let mut padded = vec![0u8; 1024];
+        padded[1020..].copy_from_slice(&547u32.to_be_bytes());
+        assert!(miller_rabin(&padded).unwrap());
+
+        // Largest primes that fit in a U64 and a U128: their top bit is set
+        assert!(miller_rabin(&(u64::MAX - 58).to_be_bytes()).unwrap());
+        assert!(!miller_rabin(&u64::MAX.to_be_bytes()).unwrap());
+        assert!(miller_rabin(&(u128::MAX - 158).to_be_bytes()).unwrap());
+        // 2^127 - 1 is a Mersenne prime
+        assert!(miller_rabin(&(u128::MAX >> 1).to_be_bytes()).unwrap());
+
+        // Any width works, as long as the number fits
+        assert!(miller_rabin_uint(&U64::from(7919u32)).unwrap());
+        assert!(miller_rabin_uint(&U256::from(7919u32)).unwrap());
+        assert!(!miller_rabin_uint(&U128::from(7917u32)).unwrap());
+        assert_eq!(
+            from_be_bytes::<{ U128::LIMBS }>(&[1, 2]),
+            U128::from(258u32)
+        );
+    }
+
+    #[test]
+    fn test_miller_rabin_with_rng() {
+        // Same seed, same bases: 1 round on a composite is a coin toss we can replay
+        let carmichael = 3215031751u32.to_be_bytes();
+        for seed in 0..20 {
+            let first =
+                miller_rabin_with_rng(&carmichael, 1, &mut ChaCha20Rng::seed_from_u64(seed));
+            let second =
+                miller_rabin_with_rng(&carmichael, 1, &mut ChaCha20Rng::seed_from_u64(seed));
+            assert_eq!(first.unwrap(), second.unwrap());
+        }
+        let mut rng = ChaCha20Rng::seed_from_u64(2357);
+        assert!(miller_rabin_with_rng(&7919u32.to_be_bytes(), 10, &mut rng).unwrap());
+        assert!(!miller_rabin_with_rng(&7917u32.to_be_bytes(), 10, &mut rng).unwrap());
+        // No rounds, no evidence against n
+        assert!(miller_rabin_with_rng(&45u32.to_be_bytes(), 0, &mut rng).unwrap());
+        assert!(matches!(
+            miller_rabin_with_rng(&[1u8; 2000], 10, &mut rng),
+            Err(MillerRabinError::TooManyBytes(2000))
+        ));
+    }
+
+    // The smallest strong pseudoprimes to all of the first k prime bases
+    const PSEUDOPRIMES: [(u128, usize); 10] = [
+        (2_047, 1),
+        (1_373_653, 2),
+        (25_326_001, 3),
+        (3_215_031_751, 4),
+        (2_152_302_898_747, 5),
+        (3_474_749_660_383, 6),
+        (341_550_071_728_321, 8),
+        (3_825_123_056_546_413_051, 11),
+        (318_665_857_834_031_151_167_461, 12),
+        (3_317_044_064_679_887_385_961_981, 13),
+    ];
+    const PRIMES: [u8; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+    #[test]
+    fn test_strong_pseudoprimes() {
+        for (n, k) in PSEUDOPRIMES {
+            let n = U128::from(n);
+            for (i, &a) in PRIMES.iter().enumerate() {
+                let expected = i < k;
+                assert_eq!(
+                    strong_probable_prime(&n, &U128::from(a)).unwrap(),
+                    expected,
+                    "{n} to base {a}"
+                );
+                if !expected {
+                    break;
+                }
+            }
+        }
+        // 3215031751 = 151 * 751 * 28351 fools bases 2, 3, 5 and 7, but not the deterministic test
+        assert!(!miller_rabin_uint_deterministic(&U64::from(3_215_031_751u64)).unwrap());
+
+        assert!(matches!(
+            strong_probable_prime(&U64::from(2047u32), &U64::from(2046u32)),
+            Err(MillerRabinError::InvalidBase)
+        ));
+        assert!(matches!(
+            strong_probable_prime(&U64::from(2047u32), &U64::ONE),
+            Err(MillerRabinError::InvalidBase)
+        ));
+    }
+
+    #[test]
+    fn test_miller_rabin_deterministic() {
+        // Against trial division
+        for n in (5u32..20_000).step_by(2) {
+            let is_prime = (3..)
+                .step_by(2)
+                .take_while(|d| d * d <= n)
+                .all(|d| n % d != 0);
+            assert_eq!(
+                miller_rabin_deterministic(&n.to_be_bytes()).unwrap(),
+                is_prime,
+                "{n}"
+            );
+        }
+        // The smallest strong pseudoprimes to each set of bases: at the bound of that set, they
+        // use the next one and get caught. There's no set after the last one.
+        for (n, _) in &PSEUDOPRIMES[..9] {
+            assert!(
+                !miller_rabin_deterministic(&n.to_be_bytes()).unwrap(),
+                "{n}"
+            );
+        }
+        // Largest primes below 2^64 and 2^81, and 2^81 - 1 = 7 * 73 * 2593 * 71119 * ...
+        assert!(miller_rabin_deterministic(&(u64::MAX - 58).to_be_bytes()).unwrap());
+        assert!(miller_rabin_uint_deterministic(&U128::from((1u128 << 81) - 51)).unwrap());
+        assert!(!miller_rabin_uint_deterministic(&U256::from((1u128 << 81) - 1)).unwrap());
+
+        assert!(matches!(
+            miller_rabin_deterministic(&3_317_044_064_679_887_385_961_981u128.to_be_bytes()),
+            Err(MillerRabinError::TooLargeForDeterministic)
+        ));
+        assert!(matches!(
+            miller_rabin_deterministic(&[0xff; 17]),
+            Err(MillerRabinError::TooLargeForDeterministic)
+        ));
+        assert_eq!(to_u128(&U256::MAX.shr_vartime(128)), Some(u128::MAX));
+        assert_eq!(to_u128(&U256::MAX.shr_vartime(127)), None);
+    }
+
     #[test]
     fn test_miller_rabin_simple_errors() {
         assert_eq!(
@@ -246,7 +635,7 @@ mod test {
         // We're asking for a random 2048 bit number and asking that it's < 100 after 100 tries
         // Yes, technically there is an extremely low probability that this test fails but...
         assert_eq!(
-            get_random(2048, U8192::from(100u32))
+            get_random(2048, U8192::from(100u32), &mut OsRng)
                 .unwrap_err()
                 .to_string(),
             "Cannot find random number with 2 <= n <= limit".to_string()
@@ -254,7 +643,7 @@ mod test {
 
         // We're asking for a 10-bit number under 512. So we have 50% chance on each random try.
         // The probability of this test failing is 2^(-100)!
-        assert!(get_random(10, U8192::from(512u32)).unwrap() <= U8192::from(512u32));
+        assert!(get_random(10, U8192::from(512u32), &mut OsRng).unwrap() <= U8192::from(512u32));
     }
 
     #[test]
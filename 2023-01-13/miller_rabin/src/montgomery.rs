@@ -0,0 +1,178 @@
+//! Montgomery arithmetic modulo an odd n, on top of crypto-bigint's `DynResidue`.
+//!
+//! With R = 2^(bits of Uint<LIMBS>), a `DynResidue` holds x as x.R mod n. The product of two
+//! representations, a.R * b.R, is brought back to a.b.R by dividing by R mod n (REDC): add the
+//! multiple of n that zeroes out the low limbs, then drop them. No division by n, ever.
+//!
+//! `DynResidue::pow` is constant-time: a fixed 4-bit window over every bit of the exponent type.
+//! For testing public numbers, `pow` below is faster with sliding windows, and only goes through
+//! the bits the exponent actually has. It's variable-time: not for secret exponents.
+
+use crypto_bigint::modular::runtime_mod::{DynResidue, DynResidueParams};
+use crypto_bigint::Uint;
+
+/// The parameters for arithmetic modulo n. None if the modulus is even, or 1.
+pub fn residue_params<const LIMBS: usize>(
+    modulus: &Uint<LIMBS>,
+) -> Option<DynResidueParams<LIMBS>> {
+    if modulus.bit_vartime(0) && modulus != &Uint::ONE {
+        Some(DynResidueParams::new(modulus))
+    } else {
+        None
+    }
+}
+
+/// base^exponent with sliding windows: runs of up to w bits which end with a 1 cost w squarings
+/// and one multiplication by a precomputed odd power of the base. That's about bits / (w + 1)
+/// multiplications, instead of bits / 2.
+pub fn pow<const LIMBS: usize>(
+    base: &DynResidue<LIMBS>,
+    exponent: &Uint<LIMBS>,
+) -> DynResidue<LIMBS> {
+    let bits = exponent.bits_vartime();
+    let w = window_size(bits);
+
+    // odd_powers[i] = base^(2i + 1)
+    let base_squared = base.square();
+    let mut odd_powers = vec![*base];
+    for i in 1..1 << (w - 1) {
+        odd_powers.push(odd_powers[i - 1] * base_squared);
+    }
+
+    let bit = |i: usize| usize::from(exponent.bit_vartime(i));
+    let mut result = DynResidue::one(*base.params());
+    // Bits above `remaining` are done
+    let mut remaining = bits;
+    while remaining > 0 {
+        let top = remaining - 1;
+        if bit(top) == 0 {
+            result = result.square();
+            remaining -= 1;
+            continue;
+        }
+        // The longest window from `top` down to `low`, of at most w bits, which ends with a 1
+        let mut low = remaining.saturating_sub(w);
+        while bit(low) == 0 {
+            low += 1;
+        }
+        let mut window = 0;
+        for i in (low..=top).rev() {
+            result = result.square();
+            window = (window << 1) | bit(i);
+        }
+        result *= odd_powers[window >> 1];
+        remaining = low;
+    }
+    result
+}
+
+// Window sizes from OpenSSL (BN_window_bits_for_exponent_size): the table of 2^(w-1) odd powers
+// has to pay for itself
+fn window_size(bits: usize) -> usize {
+    match bits {
+        672.. => 6,
+        240.. => 5,
+        80.. => 4,
+        24.. => 3,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crypto_bigint::modular::runtime_mod::DynResidue;
+    use crypto_bigint::{NonZero, Random, Uint, U128, U2048, U256, U64};
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    use crate::montgomery::{pow, residue_params};
+
+    #[test]
+    fn test_montgomery_form() {
+        assert!(residue_params(&U64::from(10u8)).is_none());
+        assert!(residue_params(&U64::ONE).is_none());
+
+        let params = residue_params(&U64::from(2357u32)).unwrap();
+        let a = DynResidue::new(&U64::from(397u32), params);
+        let b = DynResidue::new(&U64::from(1000u32), params);
+        assert_eq!(a.retrieve(), U64::from(397u32));
+        assert_eq!(DynResidue::one(params).retrieve(), U64::ONE);
+        // 397 * 1000 = 1024 (mod 2357)
+        assert_eq!((a * b).retrieve(), U64::from(1024u32));
+        // 397^-1 = 1603 (mod 2357), see the README: 397^2355 = 397^-1 by Fermat
+        let inverse = pow(&a, &U64::from(2355u32));
+        assert_eq!(inverse.retrieve(), U64::from(1603u32));
+    }
+
+    #[test]
+    fn test_full_width_modulus() {
+        // 2^128 - 159 is prime, and has its top bit set: REDC overflows past the top limb
+        let p = U128::MAX.wrapping_sub(&U128::from(158u8));
+        let params = residue_params(&p).unwrap();
+        let p_minus_one = p.wrapping_sub(&U128::ONE);
+        let x = DynResidue::new(&p_minus_one, params);
+        // (-1)^2 = 1, and Fermat: x^(p - 1) = 1
+        let one = DynResidue::one(params);
+        assert_eq!(x.square(), one);
+        assert_eq!(
+            pow(&DynResidue::new(&U128::from(3u8), params), &p_minus_one),
+            one
+        );
+    }
+
+    #[test]
+    fn test_pow_matches_constant_time_pow() {
+        let n =
+            U256::from_be_hex("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f");
+        let params = residue_params(&n).unwrap();
+        let base = DynResidue::new(&U256::from(0xdecafu32), params);
+        // Every window size, and exponents with long runs of zeros and ones
+        for exponent in [
+            U256::ZERO,
+            U256::ONE,
+            U256::from(0b1011_0001u32),
+            U256::from(u64::MAX),
+            U256::ONE.shl_vartime(200),
+            U256::MAX,
+        ] {
+            assert_eq!(pow(&base, &exponent), base.pow(&exponent));
+        }
+    }
+
+    // a.b mod n, with a full product and a division
+    fn mul_mod_reference<const LIMBS: usize>(
+        a: &Uint<LIMBS>,
+        b: &Uint<LIMBS>,
+        n: &Uint<LIMBS>,
+    ) -> Uint<LIMBS> {
+        Uint::const_rem_wide(a.mul_wide(b), n).0
+    }
+
+    #[test]
+    fn test_random_2048_bit_moduli() {
+        // Against plain arithmetic, for RSA-size odd moduli: full width, and one limb shorter
+        let mut rng = ChaCha20Rng::seed_from_u64(2048);
+        for shift in [0, 64] {
+            let n = (U2048::random(&mut rng) >> shift) | U2048::ONE;
+            let modulus = NonZero::new(n).unwrap();
+            let params = residue_params(&n).unwrap();
+            for _ in 0..8 {
+                let a = U2048::random(&mut rng).rem(&modulus);
+                let b = U2048::random(&mut rng).rem(&modulus);
+                let (a_m, b_m) = (DynResidue::new(&a, params), DynResidue::new(&b, params));
+                assert_eq!(a_m.retrieve(), a);
+                assert_eq!((a_m * b_m).retrieve(), mul_mod_reference(&a, &b, &n));
+
+                let exponent = U2048::random(&mut rng) >> 1792;
+                let mut expected = U2048::ONE;
+                for i in (0..exponent.bits_vartime()).rev() {
+                    expected = mul_mod_reference(&expected, &expected, &n);
+                    if exponent.bit_vartime(i) {
+                        expected = mul_mod_reference(&expected, &a, &n);
+                    }
+                }
+                assert_eq!(pow(&a_m, &exponent).retrieve(), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+use toy_ecc::msm::{pippenger, shamir, straus};
+use toy_ecc::{Curve, Point};
+
+// Deterministic pseudo-random 256-bit scalars
+fn scalar(i: u32) -> BigUint {
+    BigUint::from_bytes_be(&Sha256::digest(i.to_be_bytes()))
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let curve = Curve::named("secp256k1").unwrap();
+    let g = curve.generator().unwrap();
+    let order = curve.subgroup_order().unwrap();
+    let scalar = BigUint::parse_bytes(
+        b"c0ffee254729296a45a3885639ac7e10f9d54979a2a6ba8b8e2e4bdbe5d2b1f7",
+        16,
+    )
+    .unwrap();
+
+    c.bench_function("secp256k1 scalar mul (affine)", |b| {
+        b.iter(|| g.mul_affine(scalar.clone()))
+    });
+    c.bench_function("secp256k1 scalar mul (Jacobian)", |b| {
+        b.iter(|| g.mul(scalar.clone()))
+    });
+    c.bench_function("secp256k1 scalar mul (NAF)", |b| {
+        b.iter(|| g.mul_naf(scalar.clone()))
+    });
+    c.bench_function("secp256k1 scalar mul (constant-time ladder)", |b| {
+        b.iter(|| g.mul_ct(&scalar, order).unwrap())
+    });
+    for w in [4, 5] {
+        c.bench_function(&format!("secp256k1 scalar mul (wNAF, w = {w})"), |b| {
+            b.iter(|| g.mul_wnaf(scalar.clone(), w).unwrap())
+        });
+    }
+}
+
+// The ladder should take the same time for scalars with few and many bits set
+fn constant_time_benchmark(c: &mut Criterion) {
+    let curve = Curve::named("P-256").unwrap();
+    let g = curve.generator().unwrap();
+    let n = curve.subgroup_order().unwrap();
+    let low = (BigUint::from(1u8) << 255) + BigUint::from(1u8);
+    let high = n - BigUint::from(1u8);
+
+    let mut group = c.benchmark_group("P-256 constant-time ladder");
+    group.bench_function("2 bits set", |b| b.iter(|| g.mul_ct(&low, n).unwrap()));
+    group.bench_function(format!("{} bits set", high.count_ones()), |b| {
+        b.iter(|| g.mul_ct(&high, n).unwrap())
+    });
+    group.finish();
+}
+
+fn double_mul_benchmark(c: &mut Criterion) {
+    let g = Curve::named("secp256k1").unwrap().generator().unwrap();
+    let q = g.mul(scalar(0));
+    let (a, b) = (scalar(1), scalar(2));
+
+    c.bench_function("secp256k1 aP + bQ (two muls)", |bencher| {
+        bencher.iter(|| g.mul(a.clone()) + q.mul(b.clone()))
+    });
+    c.bench_function("secp256k1 aP + bQ (Shamir)", |bencher| {
+        bencher.iter(|| shamir(&a, &g, &b, &q).unwrap())
+    });
+}
+
+fn msm_benchmark(c: &mut Criterion) {
+    let g = Curve::named("secp256k1").unwrap().generator().unwrap();
+    let mut group = c.benchmark_group("secp256k1 multi-scalar mul");
+    group.sample_size(10);
+    for n in [4, 16, 64] {
+        let points: Vec<Point> = (0..n).map(|i| g.mul(scalar(1000 + i))).collect();
+        let scalars: Vec<BigUint> = (0..n).map(scalar).collect();
+
+        group.bench_with_input(BenchmarkId::new("separate muls", n), &n, |b, _| {
+            b.iter(|| {
+                points
+                    .iter()
+                    .zip(&scalars)
+                    .map(|(p, k)| p.mul(k.clone()))
+                    .reduce(|acc, p| acc + p)
+                    .unwrap()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("Straus", n), &n, |b, _| {
+            b.iter(|| straus(&points, &scalars, 4).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("Pippenger", n), &n, |b, _| {
+            b.iter(|| pippenger(&points, &scalars).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    criterion_benchmark,
+    double_mul_benchmark,
+    msm_benchmark,
+    constant_time_benchmark
+);
+criterion_main!(benches);
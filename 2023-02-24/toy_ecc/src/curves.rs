@@ -0,0 +1,190 @@
+//! Registry of named curves, for `Curve::named`.
+//!
+//! Standard curves come with their generator, its order and their cofactor. Curve25519 is given
+//! in short Weierstrass form (Wei25519, from draft-ietf-lwig-curve-representations): see
+//! `MontgomeryCurve::to_weierstrass` for the map. The toy curves are the textbook's examples.
+
+use num_bigint::BigUint;
+
+use crate::util::{bint, buint};
+use crate::{Curve, ECCError};
+
+/// Names accepted by `Curve::named`
+pub const CURVE_NAMES: [&str; 7] = [
+    "secp256k1",
+    "P-256",
+    "P-384",
+    "Curve25519",
+    "itmc-13",
+    "itmc-631",
+    "supersingular-59",
+];
+
+impl Curve {
+    /// A curve from the registry (see `CURVE_NAMES`), or `ECCError::UnknownCurve`.
+    pub fn named(name: &str) -> Result<Curve, ECCError> {
+        let curve = match name {
+            "secp256k1" => Curve::new(
+                name,
+                0,
+                0,
+                7,
+                hex("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"),
+            )
+            .with_generator_unchecked(
+                hex("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+                hex("483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"),
+                hex("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"),
+                buint(1),
+            ),
+            "P-256" => Curve::new(
+                name,
+                bint(0),
+                bint(-3),
+                bint(hex(
+                    "5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b",
+                )),
+                hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff"),
+            )
+            .with_generator_unchecked(
+                hex("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"),
+                hex("4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"),
+                hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551"),
+                buint(1),
+            ),
+            "P-384" => Curve::new(
+                name,
+                bint(0),
+                bint(-3),
+                bint(hex("b3312fa7e23ee7e4988e056be3f82d19181d9c6efe8141120314088f5013875ac656398d8a2ed19d2a85c8edd3ec2aef")),
+                hex("fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffff0000000000000000ffffffff"),
+            )
+            .with_generator_unchecked(
+                hex("aa87ca22be8b05378eb1c71ef320ad746e1d3b628ba79b9859f741e082542a385502f25dbf55296c3a545e3872760ab7"),
+                hex("3617de4a96262c6f5d9e98bf9292dc29f8f41dbd289a147ce9da3113b5f0b8c00a60b1ce1d7e819d7a431d7c90ea0e5f"),
+                hex("ffffffffffffffffffffffffffffffffffffffffffffffffc7634d81f4372ddf581a0db248b0a77aecec196accc52973"),
+                buint(1),
+            ),
+            // y^2 = x^3 + 486662.x^2 + x, with x shifted by 486662/3
+            "Curve25519" => Curve::new(
+                name,
+                buint(0),
+                hex("2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa984914a144"),
+                hex("7b425ed097b425ed097b425ed097b425ed097b425ed097b4260b5e9c7710c864"),
+                hex("7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed"),
+            )
+            .with_generator_unchecked(
+                hex("2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaad245a"),
+                hex("20ae19a1b8a086b4e01edd2c7748d14c923d4d7e6d7c61b229e9c5a27eced3d9"),
+                hex("1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed"),
+                buint(8),
+            ),
+            // Y^2 = X^3 + 3X + 8 on F13: 9 points, all multiples of (1, 8)
+            "itmc-13" => Curve::new(name, 0, 3, 8, 13).with_generator_unchecked(
+                buint(1),
+                buint(8),
+                buint(9),
+                buint(1),
+            ),
+            // Y^2 = X^3 + 30X + 34 on F631: 650 points, and all of E[5] (the Weil pairing example)
+            "itmc-631" => Curve::new(name, 0, 30, 34, 631),
+            // Y^2 = X^3 + X on F59: supersingular, with 60 points. E[5] is in E(F_59^2).
+            "supersingular-59" => Curve::new(name, 0, 1, 0, 59).with_generator_unchecked(
+                buint(35),
+                buint(31),
+                buint(5),
+                buint(12),
+            ),
+            _ => return Err(ECCError::UnknownCurve),
+        };
+        Ok(curve)
+    }
+}
+
+fn hex(s: &str) -> BigUint {
+    BigUint::parse_bytes(s.as_bytes(), 16).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::curves::CURVE_NAMES;
+    use crate::util::buint;
+    use crate::{Curve, ECCError};
+
+    #[test]
+    fn test_registry_parameters() {
+        for name in CURVE_NAMES {
+            let curve = Curve::named(name).unwrap();
+            assert_eq!(curve.name(), name);
+            assert!(curve.is_smooth(), "{name}");
+            if let Some(g) = curve.generator() {
+                assert!(curve.contains(g.clone()), "{name}");
+                let order = curve.subgroup_order().unwrap();
+                assert!(g.mul(order.clone()).is_at_infinity(), "{name}");
+                assert!(!g.is_at_infinity(), "{name}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_toy_curve_orders() {
+        for name in ["itmc-13", "supersingular-59"] {
+            let curve = Curve::named(name).unwrap();
+            let n = curve.subgroup_order().unwrap() * curve.cofactor().unwrap();
+            assert_eq!(curve.order_naive(), n, "{name}");
+            assert_eq!(
+                curve.generator().unwrap().order().unwrap(),
+                *curve.subgroup_order().unwrap()
+            );
+        }
+        assert_eq!(
+            Curve::named("itmc-631").unwrap().order().unwrap(),
+            buint(650)
+        );
+        assert!(Curve::named("itmc-631").unwrap().generator().is_none());
+    }
+
+    #[test]
+    fn test_supersingular_two_torsion() {
+        // a6 = 0: (0, 0) is the only point of order 2, not the point at infinity
+        let curve = Curve::named("supersingular-59").unwrap();
+        let t = curve.point(0, 0);
+        assert!(curve.contains(t.clone()));
+        assert!(!t.is_at_infinity());
+        assert_eq!(t.order().unwrap(), buint(2));
+        assert!((t.clone() + t.clone()).is_at_infinity());
+        assert_eq!(buint(curve.points().count()), buint(60));
+
+        // G + T has order 10, and T is 6 times a point of order 12
+        let g = curve.generator().unwrap();
+        let sum = g.clone() + t.clone();
+        assert_ne!(sum, g);
+        assert_eq!(sum.order().unwrap(), buint(10));
+        assert_eq!(sum.clone() + t.clone(), g);
+        let order_12 = curve
+            .points()
+            .find(|p| p.order().unwrap() == buint(12))
+            .unwrap();
+        assert_eq!(order_12.mul(buint(6)), t);
+    }
+
+    #[test]
+    fn test_unknown_curve() {
+        assert!(matches!(
+            Curve::named("secp256r2"),
+            Err(ECCError::UnknownCurve)
+        ));
+    }
+
+    #[test]
+    fn test_curves_are_owned() {
+        // Curves and points can outlive the scope that built them
+        fn generator(name: &str) -> crate::Point {
+            Curve::named(name).unwrap().generator().unwrap()
+        }
+        let g = generator("secp256k1");
+        let double = g.clone() + g.clone();
+        assert_eq!(double, g.mul(buint(2)));
+        assert_eq!(g.curve, Curve::named("secp256k1").unwrap());
+    }
+}
@@ -0,0 +1,126 @@
+use num_bigint::BigUint;
+
+use crate::element::Element;
+use crate::util::buint;
+use crate::{Curve, ECCError, Point};
+
+impl Curve {
+    /// Public key validation, before using a peer's point in a key agreement. The point must:
+    ///   - live on this curve (and not merely claim to: see `ecdh_attacks` for why this matters)
+    ///   - not be the point at infinity
+    ///   - be in the subgroup of prime order `order`: [order]Q = O
+    pub fn validate_public_key(&self, point: &Point, order: &BigUint) -> Result<(), ECCError> {
+        if point.curve != *self {
+            return Err(ECCError::CurveMismatch);
+        }
+        if point.is_at_infinity() {
+            return Err(ECCError::PointAtInfinity);
+        }
+        if !self.contains(point.clone()) {
+            return Err(ECCError::PointNotOnCurve);
+        }
+        if !point.mul(order.clone()).is_at_infinity() {
+            return Err(ECCError::PointNotInSubgroup);
+        }
+        Ok(())
+    }
+
+    /// Elliptic-curve Diffie-Hellman: validates the peer's public key, then returns the
+    /// x-coordinate of [private_key]peer as the shared secret.
+    pub fn ecdh(
+        &self,
+        private_key: &BigUint,
+        peer: &Point,
+        order: &BigUint,
+    ) -> Result<Element, ECCError> {
+        if private_key == &buint(0) || private_key >= order {
+            return Err(ECCError::InvalidScalar);
+        }
+        self.validate_public_key(peer, order)?;
+        let shared = peer.mul_ct(private_key, order)?;
+        if shared.is_at_infinity() {
+            // Can't happen with a validated point and a private key in [1, order - 1]
+            return Err(ECCError::PointAtInfinity);
+        }
+        Ok(shared.x)
+    }
+
+    /// ECDH without any validation of the peer's point. Don't use this!
+    /// It only exists to demonstrate the attacks that `validate_public_key` prevents.
+    /// When the shared point is the point at infinity, the returned shared secret is 0.
+    pub fn ecdh_unvalidated(&self, private_key: &BigUint, peer: &Point) -> Element {
+        peer.mul(private_key.clone()).x
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::util::buint;
+    use crate::{Curve, ECCError};
+
+    // y^2 = x^3 + x + 28 on F10007 has 9851 points: a prime, so every point but O generates the group.
+    fn curve_prime_order() -> Curve {
+        Curve::new("prime order", 0, 1, 28, 10007)
+    }
+
+    #[test]
+    fn test_ecdh_agreement() {
+        let c = curve_prime_order();
+        let n = buint(9851);
+        let g = c.point(2, 4582);
+        assert!(c.contains(g.clone()));
+
+        let (alice, bob) = (buint(1234), buint(5678));
+        let alice_public = g.mul(alice.clone());
+        let bob_public = g.mul(bob.clone());
+
+        let alice_secret = c.ecdh(&alice, &bob_public, &n).unwrap();
+        let bob_secret = c.ecdh(&bob, &alice_public, &n).unwrap();
+        assert_eq!(alice_secret, bob_secret);
+        assert_eq!(alice_secret, g.mul(buint(1234 * 5678)).x);
+    }
+
+    #[test]
+    fn test_public_key_validation() {
+        let c = curve_prime_order();
+        let n = buint(9851);
+
+        assert!(matches!(
+            c.validate_public_key(&c.point_at_infinity(), &n),
+            Err(ECCError::PointAtInfinity)
+        ));
+        assert!(matches!(
+            c.validate_public_key(&c.point(1, 1), &n),
+            Err(ECCError::PointNotOnCurve)
+        ));
+        let other = Curve::new("other", 0, 1, 24, 10007);
+        assert!(matches!(
+            c.validate_public_key(&other.point(1, 1), &n),
+            Err(ECCError::CurveMismatch)
+        ));
+
+        // y^2 = x^3 + x + 24 on F10007 has 10056 = 24 * 419 points. With n = 419,
+        // (1, 1158) isn't in the prime-order subgroup, while 24 * (1, 1158) is.
+        let p = other.point(1, 1158);
+        assert!(other.contains(p.clone()));
+        assert!(matches!(
+            other.validate_public_key(&p, &buint(419)),
+            Err(ECCError::PointNotInSubgroup)
+        ));
+        assert!(other
+            .validate_public_key(&p.mul(buint(24)), &buint(419))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_ecdh_rejects_invalid_private_keys() {
+        let c = curve_prime_order();
+        let n = buint(9851);
+        let g = c.point(2, 4582);
+        assert!(matches!(
+            c.ecdh(&buint(0), &g, &n),
+            Err(ECCError::InvalidScalar)
+        ));
+        assert!(matches!(c.ecdh(&n, &g, &n), Err(ECCError::InvalidScalar)));
+    }
+}
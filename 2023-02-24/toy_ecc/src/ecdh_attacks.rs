@@ -0,0 +1,252 @@
+//! What goes wrong when ECDH skips public key validation.
+//!
+//! Both attacks below rely on the same observation: the addition formulas only involve a2 and a4,
+//! never a6. A victim computing [k]Q for a point Q that isn't on its curve is really computing on
+//! another curve y^2 = x^3 + a2.x^2 + a4.x + a6', whichever a6' makes Q fit.
+//!   - small-subgroup attack: Q is on the victim's curve, but has small order. The shared secret
+//!     then takes one of a handful of values, and leaks k mod (order of Q).
+//!   - invalid-curve attack: Q is on a weak curve (another a6) with a smooth group order. Repeating
+//!     the small-subgroup trick on enough weak curves leaks k modulo many small primes; the Chinese
+//!     Remainder Theorem then recovers k entirely.
+//!
+//! The shared secret is an x-coordinate, and x([t]Q) = x([-t]Q): each query only gives k mod m up
+//! to a sign. We resolve the ambiguity at the end, by checking candidates against the victim's public key.
+//!
+//! These demos are meant for toy curves: we need to count points on the weak curves, and factor their orders.
+
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::element::Element;
+use crate::util::{bint, buint, crt, factor, gcd};
+use crate::{Curve, ECCError, Point};
+
+/// A server holding a static ECDH private key. For each incoming public key, it answers with a
+/// key confirmation tag derived from the shared secret, like a handshake would.
+pub struct Victim {
+    curve: Curve,
+    order: BigUint,
+    private_key: BigUint,
+    validate: bool,
+}
+
+impl Victim {
+    pub fn new(curve: &Curve, order: BigUint, private_key: BigUint, validate: bool) -> Self {
+        Self {
+            curve: curve.clone(),
+            order,
+            private_key,
+            validate,
+        }
+    }
+
+    pub fn public_key(&self, generator: &Point) -> Point {
+        generator.mul(self.private_key.clone())
+    }
+
+    pub fn respond(&self, peer: &Point) -> Result<[u8; 32], ECCError> {
+        let shared = if self.validate {
+            self.curve.ecdh(&self.private_key, peer, &self.order)?
+        } else {
+            self.curve.ecdh_unvalidated(&self.private_key, peer)
+        };
+        Ok(tag(&shared))
+    }
+}
+
+fn tag(shared_secret: &Element) -> [u8; 32] {
+    Sha256::digest(shared_secret.value().to_bytes_be()).into()
+}
+
+/// k = ±residue (mod modulus)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leak {
+    pub residue: BigUint,
+    pub modulus: BigUint,
+}
+
+/// Small-subgroup attack against a victim whose curve has `group_order` = cofactor * n points.
+/// For each prime power m dividing the cofactor, we send a point of order m and find out k mod m (up to sign).
+/// This assumes the m-torsion part of the group is cyclic (e.g. not Z2 x Z4 when 8 divides the cofactor).
+pub fn small_subgroup_attack(
+    victim: &Victim,
+    group_order: &BigUint,
+    cofactor: &BigUint,
+) -> Result<Vec<Leak>, ECCError> {
+    let mut leaks = vec![];
+    for m in prime_power_factors(cofactor) {
+        leaks.push(leak_residue(victim, &victim.curve, group_order, &m)?);
+    }
+    Ok(leaks)
+}
+
+/// Invalid-curve attack: recovers the victim's private key from points on weak curves
+/// y^2 = x^3 + a2.x^2 + a4.x + b, for b != a6. Only uses small prime power factors (<= `max_factor`)
+/// of the weak curves' orders, and stops once their product exceeds the victim's subgroup order.
+pub fn invalid_curve_attack(
+    victim: &Victim,
+    generator: &Point,
+    max_factor: u64,
+) -> Result<BigUint, ECCError> {
+    let curve = &victim.curve;
+    let p = curve.p.clone();
+    let mut leaks: Vec<Leak> = vec![];
+    let mut product = buint(1);
+
+    let mut b = buint(0);
+    while product <= victim.order {
+        b += buint(1);
+        if b >= p {
+            return Err(ECCError::AttackFailed);
+        }
+        let weak = Curve::new(
+            "weak curve",
+            bint(curve.a2.value()),
+            bint(curve.a4.value()),
+            bint(b.clone()),
+            p.clone(),
+        );
+        if weak.a6 == curve.a6 || !weak.is_smooth() {
+            continue;
+        }
+        let weak_order = weak.order()?;
+        for m in prime_power_factors(&weak_order) {
+            // Only small factors, coprime to what we already know
+            if m > buint(max_factor) || gcd(&m, &product) != buint(1) {
+                continue;
+            }
+            leaks.push(leak_residue(victim, &weak, &weak_order, &m)?);
+            product *= m;
+        }
+    }
+
+    // Each leak gives two possible residues: try all sign combinations
+    let public_key = victim.public_key(generator);
+    for signs in 0..(1u64 << leaks.len()) {
+        let mut candidate = (buint(0), buint(1));
+        for (i, leak) in leaks.iter().enumerate() {
+            let residue = if signs >> i & 1 == 1 {
+                (&leak.modulus - &leak.residue) % &leak.modulus
+            } else {
+                leak.residue.clone()
+            };
+            candidate = crt(&candidate, &(residue, leak.modulus.clone()));
+        }
+        let k = candidate.0 % &victim.order;
+        if generator.mul(k.clone()) == public_key {
+            return Ok(k);
+        }
+    }
+    Err(ECCError::AttackFailed)
+}
+
+// Sends the victim a point of order m on `curve` (whose order is `curve_order`), then brute-forces
+// the answer to learn k mod m, up to sign.
+fn leak_residue(
+    victim: &Victim,
+    curve: &Curve,
+    curve_order: &BigUint,
+    m: &BigUint,
+) -> Result<Leak, ECCError> {
+    let q = point_of_order(curve, curve_order, m).ok_or(ECCError::AttackFailed)?;
+
+    // The victim only knows about its own curve: the point is "moved" there as-is.
+    let sent = victim.curve.point(bint(q.x.value()), bint(q.y.value()));
+    let answer = victim.respond(&sent)?;
+
+    // Brute-force t such that x([t]Q) matches the victim's answer
+    let mut multiple = curve.point_at_infinity();
+    let mut t = buint(0);
+    while &t < m {
+        if tag(&multiple.x) == answer {
+            return Ok(Leak {
+                residue: t,
+                modulus: m.clone(),
+            });
+        }
+        multiple = multiple + q.clone();
+        t += buint(1);
+    }
+    Err(ECCError::AttackFailed)
+}
+
+// Finds a point of exact order m, by multiplying points by (curve_order / m)
+fn point_of_order(curve: &Curve, curve_order: &BigUint, m: &BigUint) -> Option<Point> {
+    let cofactor = curve_order / m;
+    let primes: Vec<BigUint> = factor(m).into_iter().map(|(prime, _)| prime).collect();
+
+    let mut x = buint(0);
+    while x < curve.p {
+        let x_elem = curve.element(bint(x.clone()));
+        if let Some(y) = curve.rhs(&x_elem).sqrt() {
+            let q = curve
+                .point(bint(x.clone()), bint(y.value()))
+                .mul(cofactor.clone());
+            let exact =
+                !q.is_at_infinity() && primes.iter().all(|r| !q.mul(m / r).is_at_infinity());
+            if exact {
+                return Some(q);
+            }
+        }
+        x += buint(1);
+    }
+    None
+}
+
+// Factors n, grouping equal primes: 24 -> [8, 3]
+fn prime_power_factors(n: &BigUint) -> Vec<BigUint> {
+    factor(n)
+        .into_iter()
+        .map(|(prime, exponent)| prime.pow(exponent))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ecdh_attacks::{invalid_curve_attack, small_subgroup_attack, Victim};
+    use crate::util::buint;
+    use crate::{Curve, ECCError};
+
+    #[test]
+    fn test_invalid_curve_attack() {
+        // y^2 = x^3 + x + 28 on F10007 has prime order 9851
+        let c = Curve::new("prime order", 0, 1, 28, 10007);
+        let g = c.point(2, 4582);
+        let secret = buint(7777);
+
+        let victim = Victim::new(&c, buint(9851), secret.clone(), false);
+        assert_eq!(invalid_curve_attack(&victim, &g, 100).unwrap(), secret);
+
+        // With validation on, the victim refuses points from weak curves
+        let careful = Victim::new(&c, buint(9851), secret, true);
+        assert!(matches!(
+            invalid_curve_attack(&careful, &g, 100),
+            Err(ECCError::PointNotOnCurve)
+        ));
+    }
+
+    #[test]
+    fn test_small_subgroup_attack() {
+        // y^2 = x^3 + x + 54 on F10007 has 10090 = 10 * 1009 points
+        let c = Curve::new("cofactor 10", 0, 1, 54, 10007);
+        let secret = buint(333);
+
+        let victim = Victim::new(&c, buint(1009), secret.clone(), false);
+        let leaks = small_subgroup_attack(&victim, &buint(10090), &buint(10)).unwrap();
+        assert_eq!(leaks.len(), 2);
+        for leak in leaks {
+            let expected = &secret % &leak.modulus;
+            assert!(
+                leak.residue == expected
+                    || leak.residue == (&leak.modulus - &expected) % &leak.modulus
+            );
+        }
+
+        // With validation on, points of small order are rejected
+        let careful = Victim::new(&c, buint(1009), secret, true);
+        assert!(matches!(
+            small_subgroup_attack(&careful, &buint(10090), &buint(10)),
+            Err(ECCError::PointNotInSubgroup)
+        ));
+    }
+}
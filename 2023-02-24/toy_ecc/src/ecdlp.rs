@@ -0,0 +1,364 @@
+//! Solving the elliptic curve discrete logarithm problem: finding k from P and Q = [k]P.
+//!
+//! Each solver reports how many group operations it took, to compare with theory:
+//!   - brute force: up to n additions
+//!   - baby-step giant-step: about 2.sqrt(n) additions, and sqrt(n) points in memory
+//!   - Pollard's rho: about sqrt(pi.n / 2) additions, and only the distinguished points in memory
+//!   - Pohlig-Hellman: reduces the problem to the prime factors q of n, for about the sum of e.sqrt(q)
+//!
+//! Here n is the order of P. Only the first three are cheap on small groups, and the last one on
+//! smooth ones: on a well-chosen curve, all of them are hopeless.
+
+use std::collections::HashMap;
+
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::element::Element;
+use crate::util::{bint, buint, crt, factor, point_key};
+use crate::{ECCError, Point};
+
+/// Number of precomputed steps in Pollard's rho walk (Teske's r-adding walk)
+const RHO_PARTITIONS: usize = 20;
+
+/// k such that Q = [k]P, and the number of group operations it took to find it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dlog {
+    pub k: BigUint,
+    pub iterations: u64,
+}
+
+/// Tries k = 0, 1, 2... until [k]P = Q.
+pub fn brute_force(p: &Point, q: &Point, order: &BigUint) -> Result<Dlog, ECCError> {
+    check_curves(p, q)?;
+    let target = point_key(q);
+    let mut multiple = p.curve.point_at_infinity();
+    let mut k = buint(0);
+    let mut iterations = 0;
+    while &k < order {
+        if point_key(&multiple) == target {
+            return Ok(Dlog { k, iterations });
+        }
+        multiple = multiple + p.clone();
+        k += buint(1);
+        iterations += 1;
+    }
+    Err(ECCError::NoDiscreteLog)
+}
+
+/// Baby-step giant-step: with m = ceil(sqrt(n)), stores [j]P for 0 <= j < m,
+/// then looks for Q - [i.m]P in that table. k = i.m + j.
+pub fn baby_step_giant_step(p: &Point, q: &Point, order: &BigUint) -> Result<Dlog, ECCError> {
+    check_curves(p, q)?;
+    let m = ceil_sqrt(order);
+    let mut iterations = 0;
+
+    let mut baby_steps = HashMap::new();
+    let mut baby = p.curve.point_at_infinity();
+    let mut j = buint(0);
+    while j < m {
+        baby_steps.entry(point_key(&baby)).or_insert(j.clone());
+        baby = baby + p.clone();
+        j += buint(1);
+        iterations += 1;
+    }
+
+    let giant_step = -p.mul(m.clone());
+    let mut giant = q.clone();
+    let mut i = buint(0);
+    while i < m {
+        if let Some(j) = baby_steps.get(&point_key(&giant)) {
+            let k = (&i * &m + j) % order;
+            return Ok(Dlog { k, iterations });
+        }
+        giant = giant + giant_step.clone();
+        i += buint(1);
+        iterations += 1;
+    }
+    Err(ECCError::NoDiscreteLog)
+}
+
+/// Pollard's rho, with distinguished points. Requires a prime order: otherwise b2 - b below may not
+/// be invertible mod n, and this fails with `NotInvertible` (see `pohlig_hellman` for those).
+///
+/// Walks X = [a]P + [b]Q through the group, with a pseudo-random step depending on X, until X is
+/// distinguished (its x-coordinate ends with enough zero bits). When two walks reach the same
+/// distinguished point with different (a, b), then a1 + b1.k = a2 + b2.k (mod n), which gives k.
+/// Walks are deterministic: they start from points derived from their index.
+pub fn pollard_rho(p: &Point, q: &Point, order: &BigUint) -> Result<Dlog, ECCError> {
+    check_curves(p, q)?;
+    if order < &buint(1 << 8) {
+        return brute_force(p, q, order);
+    }
+
+    let (distinguished_bits, max_walk_length, max_iterations) = rho_bounds(order);
+
+    let steps: Vec<(BigUint, BigUint, Point)> = (0..RHO_PARTITIONS)
+        .map(|j| {
+            let c = derive_scalar(b"step a", j, order);
+            let d = derive_scalar(b"step b", j, order);
+            let step = p.mul(c.clone()) + q.mul(d.clone());
+            (c, d, step)
+        })
+        .collect();
+
+    let mut distinguished: HashMap<(BigUint, BigUint), (BigUint, BigUint)> = HashMap::new();
+    let mut iterations = 0;
+    let mut walk = 0;
+    while iterations < max_iterations {
+        let mut a = derive_scalar(b"start a", walk, order);
+        let mut b = derive_scalar(b"start b", walk, order);
+        let mut x = p.mul(a.clone()) + q.mul(b.clone());
+        walk += 1;
+
+        for _ in 0..max_walk_length {
+            if is_distinguished(&x, distinguished_bits) {
+                let key = point_key(&x);
+                match distinguished.get(&key) {
+                    Some((a2, b2)) if *b2 != b => {
+                        // a + b.k = a2 + b2.k  =>  k = (a - a2) / (b2 - b)
+                        let num = Element::new(bint(a.clone()) - bint(a2.clone()), order.clone());
+                        let den = Element::new(bint(b2.clone()) - bint(b.clone()), order.clone());
+                        let k = num.checked_div(&den)?.value();
+                        if point_key(&p.mul(k.clone())) == point_key(q) {
+                            return Ok(Dlog { k, iterations });
+                        }
+                        return Err(ECCError::NoDiscreteLog);
+                    }
+                    Some(_) => {}
+                    None => {
+                        distinguished.insert(key, (a, b));
+                    }
+                }
+                break;
+            }
+            let (c, d, step) = &steps[partition(&x)];
+            x = x + step.clone();
+            a = (a + c) % order;
+            b = (b + d) % order;
+            iterations += 1;
+        }
+    }
+    Err(ECCError::NoDiscreteLog)
+}
+
+/// Pohlig-Hellman: solves the problem in each subgroup of prime power order q^e dividing n,
+/// one base-q digit at a time (with baby-step giant-step), then recombines with the CRT.
+/// Only the group operations of the sub-problems are counted in `iterations`.
+pub fn pohlig_hellman(p: &Point, q: &Point, order: &BigUint) -> Result<Dlog, ECCError> {
+    check_curves(p, q)?;
+    let mut solution = (buint(0), buint(1));
+    let mut iterations = 0;
+
+    for (prime, exponent) in factor(order) {
+        let prime_power = prime.pow(exponent);
+        let cofactor = order / &prime_power;
+        // Both of order dividing q^e
+        let p_i = p.mul(cofactor.clone());
+        let q_i = q.mul(cofactor);
+        // Of order q
+        let base = p_i.mul(&prime_power / &prime);
+
+        // k_i = d_0 + d_1.q + ... + d_(e-1).q^(e-1)
+        let mut k_i = buint(0);
+        for i in 0..exponent {
+            // [q^(e-1-i)](Q_i - [k_i]P_i) = [d_i]base
+            let target = (q_i.clone() + -p_i.mul(k_i.clone())).mul(prime.pow(exponent - 1 - i));
+            let digit = baby_step_giant_step(&base, &target, &prime)?;
+            iterations += digit.iterations;
+            k_i += digit.k * prime.pow(i);
+        }
+        solution = crt(&solution, &(k_i, prime_power));
+    }
+
+    let k = solution.0 % order;
+    if point_key(&p.mul(k.clone())) == point_key(q) {
+        Ok(Dlog { k, iterations })
+    } else {
+        // Each subgroup had a solution, but Q isn't in the subgroup generated by P
+        Err(ECCError::NoDiscreteLog)
+    }
+}
+
+fn check_curves(p: &Point, q: &Point) -> Result<(), ECCError> {
+    if p.curve != q.curve {
+        return Err(ECCError::CurveMismatch);
+    }
+    Ok(())
+}
+
+pub(crate) fn ceil_sqrt(n: &BigUint) -> BigUint {
+    let root = n.sqrt();
+    if &root * &root == *n {
+        root
+    } else {
+        root + buint(1)
+    }
+}
+
+// Distinguished bits, maximum walk length and maximum number of iterations for Pollard's rho.
+// Walks are about 2^distinguished_bits long, which is around n^(1/4). Both maximums saturate:
+// past 2^64 group operations, nothing is going to finish anyway.
+fn rho_bounds(order: &BigUint) -> (u64, u64, u64) {
+    let distinguished_bits = order.bits() / 4;
+    let max_walk_length = 20 << distinguished_bits.min(58);
+    // Way more than the sqrt(pi.n / 2) we expect: Q probably isn't a multiple of P
+    let max_iterations = u64::try_from(ceil_sqrt(order))
+        .unwrap_or(u64::MAX)
+        .saturating_mul(100)
+        .saturating_add(10_000);
+    (distinguished_bits, max_walk_length, max_iterations)
+}
+
+fn derive_scalar(label: &[u8], index: usize, order: &BigUint) -> BigUint {
+    let digest = Sha256::new()
+        .chain_update(label)
+        .chain_update(index.to_be_bytes())
+        .finalize();
+    BigUint::from_bytes_be(&digest) % order
+}
+
+fn is_distinguished(point: &Point, bits: u64) -> bool {
+    point
+        .x
+        .value()
+        .trailing_zeros()
+        .is_none_or(|zeros| zeros >= bits)
+}
+
+// Which step to take from a point: depends on its x-coordinate only, so that the walk is a function of the point
+fn partition(point: &Point) -> usize {
+    let bucket = &point.x.value() % buint(RHO_PARTITIONS);
+    bucket.iter_u64_digits().next().unwrap_or(0) as usize
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ecdlp::{
+        baby_step_giant_step, brute_force, pohlig_hellman, pollard_rho, rho_bounds,
+    };
+    use crate::util::buint;
+    use crate::{Curve, ECCError};
+
+    #[test]
+    fn test_solvers_agree() {
+        // y^2 = x^3 + x + 28 on F10007 has prime order 9851
+        let c = Curve::new("c", 0, 1, 28, 10007);
+        let n = buint(9851);
+        let p = c.point(2, 4582);
+        for k in [0, 1, 2, 1234, 7777, 9850] {
+            let q = p.mul(buint(k));
+            assert_eq!(brute_force(&p, &q, &n).unwrap().k, buint(k));
+            assert_eq!(baby_step_giant_step(&p, &q, &n).unwrap().k, buint(k));
+            assert_eq!(pollard_rho(&p, &q, &n).unwrap().k, buint(k));
+            assert_eq!(pohlig_hellman(&p, &q, &n).unwrap().k, buint(k));
+        }
+        let brute = brute_force(&p, &p.mul(buint(7777)), &n).unwrap();
+        assert_eq!(brute.iterations, 7777);
+    }
+
+    #[test]
+    fn test_rho_cost_grows_like_sqrt_n() {
+        // Curves with subgroups of prime order n ~ 10^4, 10^6, 5.10^7
+        let small = Curve::new("c", 0, 1, 28, 10007);
+        let medium = Curve::new("c", 0, 3, 7, 1000003);
+        // 100011434 = 2 * 50005717 points
+        let large = Curve::new("c", 0, 1, 12, 100000007);
+        let cases = [
+            (small.point(2, 4582), buint(9851)),
+            (medium.points().nth(1).unwrap(), buint(999853)),
+            (large.point(2, 32612002).mul(buint(2)), buint(50005717)),
+        ];
+        for (p, n) in cases {
+            let k = &n * buint(2) / buint(3);
+            let q = p.mul(k.clone());
+            let rho = pollard_rho(&p, &q, &n).unwrap();
+            assert_eq!(rho.k, k);
+            // Theory says sqrt(pi.n / 2) ~ 1.25 sqrt(n): allow for bad luck and distinguished points
+            let sqrt_n = n.sqrt().iter_u64_digits().next().unwrap();
+            assert!(
+                rho.iterations < 8 * sqrt_n,
+                "{} iterations for n = {}",
+                rho.iterations,
+                n
+            );
+
+            let bsgs = baby_step_giant_step(&p, &q, &n).unwrap();
+            assert_eq!(bsgs.k, k);
+            assert!(bsgs.iterations <= 2 * (sqrt_n + 1));
+        }
+    }
+
+    #[test]
+    fn test_pohlig_hellman_smooth_order() {
+        // y^2 = x^3 + x + 14 on F100000007 has 100004412 = 2^2 * 3 * 29 * 41 * 43 * 163 points, generated by (1, 4)
+        let c = Curve::new("c", 0, 1, 14, 100000007);
+        let n = buint(100004412);
+        let p = c.point(1, 4);
+        let q = p.mul(buint(87654321));
+        let dlog = pohlig_hellman(&p, &q, &n).unwrap();
+        assert_eq!(dlog.k, buint(87654321));
+        // Sum of 2.sqrt(q) for each prime factor: way less than sqrt(n) = 10000
+        assert!(dlog.iterations < 100);
+    }
+
+    #[test]
+    fn test_rho_composite_order() {
+        // 10090 = 2 * 5 * 1009 points, generated by (2, 8): a collision gives an even b2 - b
+        let c = Curve::new("c", 0, 1, 54, 10007);
+        let n = buint(10090);
+        let p = c.point(2, 8);
+        let q = p.mul(buint(1234));
+        assert!(matches!(
+            pollard_rho(&p, &q, &n),
+            Err(ECCError::NotInvertible)
+        ));
+        assert_eq!(pohlig_hellman(&p, &q, &n).unwrap().k, buint(1234));
+    }
+
+    #[test]
+    fn test_rho_bounds_saturate() {
+        // A 256-bit order: 2^64 distinguished bits, and sqrt(n) doesn't fit in a u64
+        let n = (buint(1) << 256) - buint(189);
+        let (bits, max_walk_length, max_iterations) = rho_bounds(&n);
+        assert_eq!(bits, 64);
+        assert_eq!(max_walk_length, 20 << 58);
+        assert_eq!(max_iterations, u64::MAX);
+
+        let (bits, max_walk_length, max_iterations) = rho_bounds(&buint(9851));
+        assert_eq!(bits, 3);
+        assert_eq!(max_walk_length, 160);
+        assert_eq!(max_iterations, 100 * 100 + 10_000);
+    }
+
+    #[test]
+    fn test_no_discrete_log() {
+        // 10056 = 24 * 419 points: [24](1, 1158) has order 419, and (1, 1158) isn't one of its multiples
+        let c = Curve::new("c", 0, 1, 24, 10007);
+        let q = c.point(1, 1158);
+        let p = q.mul(buint(24));
+        let n = buint(419);
+        assert!(matches!(
+            brute_force(&p, &q, &n),
+            Err(ECCError::NoDiscreteLog)
+        ));
+        assert!(matches!(
+            baby_step_giant_step(&p, &q, &n),
+            Err(ECCError::NoDiscreteLog)
+        ));
+        assert!(matches!(
+            pohlig_hellman(&p, &q, &n),
+            Err(ECCError::NoDiscreteLog)
+        ));
+        assert!(matches!(
+            pollard_rho(&p, &q, &n),
+            Err(ECCError::NoDiscreteLog)
+        ));
+
+        let other = Curve::new("other", 0, 1, 28, 10007);
+        assert!(matches!(
+            brute_force(&p, &other.point(2, 4582), &n),
+            Err(ECCError::CurveMismatch)
+        ));
+    }
+}
@@ -0,0 +1,441 @@
+use hmac::{Hmac, Mac};
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::element::Element;
+use crate::msm::shamir;
+use crate::util::buint;
+use crate::{ECCError, Point};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// ECDSA signature. `recovery_id` lets verifiers recover the public key from the signature:
+///   - bit 0 is the parity of R's y-coordinate
+///   - bit 1 is set when R's x-coordinate was reduced mod n (i.e. x >= n)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    pub r: BigUint,
+    pub s: BigUint,
+    pub recovery_id: u8,
+}
+
+/// ECDSA over any curve, given a base point and its (prime) order.
+/// Messages are hashed with SHA-256, and nonces are derived deterministically (RFC 6979).
+#[derive(Debug, Clone)]
+pub struct Ecdsa {
+    generator: Point,
+    order: BigUint,
+}
+
+impl Ecdsa {
+    pub fn new(generator: Point, order: BigUint) -> Self {
+        Self { generator, order }
+    }
+
+    pub fn public_key(&self, private_key: &BigUint) -> Result<Point, ECCError> {
+        self.check_scalar(private_key)?;
+        self.generator.mul_ct(private_key, &self.order)
+    }
+
+    pub fn sign(&self, private_key: &BigUint, message: &[u8]) -> Result<Signature, ECCError> {
+        self.sign_prehashed(private_key, &Sha256::digest(message))
+    }
+
+    /// Signs a SHA-256 digest. Signatures are normalised to low-S form: s <= n/2.
+    pub fn sign_prehashed(
+        &self,
+        private_key: &BigUint,
+        digest: &[u8],
+    ) -> Result<Signature, ECCError> {
+        self.check_scalar(private_key)?;
+        let n = &self.order;
+        let e = self.scalar(self.bits2int(digest));
+        let d = self.scalar(private_key.clone());
+
+        let mut nonces = Rfc6979::new(private_key, digest, n);
+        loop {
+            let k = nonces.next_nonce();
+            let point = self.generator.mul_ct(&k, n)?;
+            if point.is_at_infinity() {
+                continue;
+            }
+            let x = point.x.value();
+            let r = self.scalar(x.clone());
+            if r.is_zero() {
+                continue;
+            }
+            let s = (e.clone() + r.clone() * d.clone()) / self.scalar(k);
+            if s.is_zero() {
+                continue;
+            }
+
+            let mut recovery_id = point.y.value().bit(0) as u8;
+            if &x >= n {
+                recovery_id |= 2;
+            }
+
+            // Low-S normalisation: (r, s) and (r, n - s) are both valid signatures.
+            // Flipping s amounts to negating R, so the parity of R's y-coordinate flips too.
+            let s = if s.value() > n >> 1 {
+                recovery_id ^= 1;
+                (-s).value()
+            } else {
+                s.value()
+            };
+
+            return Ok(Signature {
+                r: r.value(),
+                s,
+                recovery_id,
+            });
+        }
+    }
+
+    pub fn verify(&self, public_key: &Point, message: &[u8], signature: &Signature) -> bool {
+        self.verify_prehashed(public_key, &Sha256::digest(message), signature)
+    }
+
+    pub fn verify_prehashed(
+        &self,
+        public_key: &Point,
+        digest: &[u8],
+        signature: &Signature,
+    ) -> bool {
+        let curve = &self.generator.curve;
+        if public_key.is_at_infinity() || !curve.contains(public_key.clone()) {
+            return false;
+        }
+        if self.check_scalar(&signature.r).is_err() || self.check_scalar(&signature.s).is_err() {
+            return false;
+        }
+
+        let e = self.scalar(self.bits2int(digest));
+        let s_inv = self.scalar(1) / self.scalar(signature.s.clone());
+        let u1 = e * s_inv.clone();
+        let u2 = self.scalar(signature.r.clone()) * s_inv;
+
+        let point = match shamir(&u1.value(), &self.generator, &u2.value(), public_key) {
+            Ok(point) if !point.is_at_infinity() => point,
+            _ => return false,
+        };
+        self.scalar(point.x.value()).value() == signature.r
+    }
+
+    pub fn recover(&self, message: &[u8], signature: &Signature) -> Result<Point, ECCError> {
+        self.recover_prehashed(&Sha256::digest(message), signature)
+    }
+
+    /// Recovers the public key from a signature and its recovery id:
+    ///     Q = r^-1 (s.R - e.G)
+    /// where R is the point with x-coordinate r (+ n if bit 1 of the recovery id is set), and with
+    /// the y-coordinate parity given by bit 0 of the recovery id.
+    pub fn recover_prehashed(
+        &self,
+        digest: &[u8],
+        signature: &Signature,
+    ) -> Result<Point, ECCError> {
+        self.check_scalar(&signature.r)
+            .and(self.check_scalar(&signature.s))
+            .map_err(|_| ECCError::InvalidSignature)?;
+
+        let curve = &self.generator.curve;
+        let mut x = signature.r.clone();
+        if signature.recovery_id & 2 == 2 {
+            x += &self.order;
+        }
+        if x >= curve.p {
+            return Err(ECCError::InvalidSignature);
+        }
+        let x = curve.element(x);
+        let mut y = curve.rhs(&x).sqrt().ok_or(ECCError::InvalidSignature)?;
+        if y.value().bit(0) != (signature.recovery_id & 1 == 1) {
+            y = -y;
+        }
+        let r_point = curve.point(x.value(), y.value());
+
+        let e = self.scalar(self.bits2int(digest));
+        let r_inv = self.scalar(1) / self.scalar(signature.r.clone());
+        let u1 = -(e * r_inv.clone());
+        let u2 = self.scalar(signature.s.clone()) * r_inv;
+
+        let public_key = self.generator.mul(u1.value()) + r_point.mul(u2.value());
+        if public_key.is_at_infinity() {
+            return Err(ECCError::InvalidSignature);
+        }
+        Ok(public_key)
+    }
+
+    // Scalars (private keys, r and s) must be in [1, n-1]
+    fn check_scalar(&self, scalar: &BigUint) -> Result<(), ECCError> {
+        if scalar == &buint(0) || scalar >= &self.order {
+            return Err(ECCError::InvalidScalar);
+        }
+        Ok(())
+    }
+
+    fn scalar<T: num_bigint::ToBigInt>(&self, value: T) -> Element {
+        Element::new(value, self.order.clone())
+    }
+
+    fn bits2int(&self, bytes: &[u8]) -> BigUint {
+        bits2int(bytes, self.order.bits())
+    }
+}
+
+// RFC 6979, section 2.3.2: interprets bytes as a big-endian integer, keeping the leftmost `qlen` bits
+fn bits2int(bytes: &[u8], qlen: u64) -> BigUint {
+    let value = BigUint::from_bytes_be(bytes);
+    let blen = bytes.len() as u64 * 8;
+    if blen > qlen {
+        value >> (blen - qlen)
+    } else {
+        value
+    }
+}
+
+// RFC 6979, section 2.3.3: integer to big-endian octet string of length rlen = ceil(qlen / 8)
+fn int2octets(value: &BigUint, rlen: usize) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    let mut out = vec![0u8; rlen.saturating_sub(bytes.len())];
+    out.extend_from_slice(&bytes);
+    out
+}
+
+/// Deterministic nonce generation from RFC 6979, section 3.2, with HMAC-SHA256.
+/// Each call to `next_nonce` yields the next candidate k in [1, q-1].
+struct Rfc6979 {
+    k: Vec<u8>,
+    v: Vec<u8>,
+    q: BigUint,
+    first: bool,
+}
+
+impl Rfc6979 {
+    fn new(private_key: &BigUint, digest: &[u8], q: &BigUint) -> Self {
+        let rlen = q.bits().div_ceil(8) as usize;
+        let x = int2octets(private_key, rlen);
+        let h = int2octets(&(bits2int(digest, q.bits()) % q), rlen);
+
+        // Steps b. through g.
+        let mut k = vec![0u8; 32];
+        let mut v = vec![1u8; 32];
+        k = hmac(&k, &[&v, &[0x00], &x, &h]);
+        v = hmac(&k, &[&v]);
+        k = hmac(&k, &[&v, &[0x01], &x, &h]);
+        v = hmac(&k, &[&v]);
+
+        Self {
+            k,
+            v,
+            q: q.clone(),
+            first: true,
+        }
+    }
+
+    fn next_nonce(&mut self) -> BigUint {
+        loop {
+            // When a candidate is rejected (here or by the caller), step h.3. updates K and V
+            if !self.first {
+                self.k = hmac(&self.k, &[&self.v, &[0x00]]);
+                self.v = hmac(&self.k, &[&self.v]);
+            }
+            self.first = false;
+
+            // Step h.
+            let qlen = self.q.bits();
+            let mut t = vec![];
+            while (t.len() as u64) * 8 < qlen {
+                self.v = hmac(&self.k, &[&self.v]);
+                t.extend_from_slice(&self.v);
+            }
+            let k = bits2int(&t, qlen);
+            if k > buint(0) && k < self.q {
+                return k;
+            }
+        }
+    }
+}
+
+fn hmac(key: &[u8], data: &[&[u8]]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC can take a key of any size");
+    for chunk in data {
+        mac.update(chunk);
+    }
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use sha2::{Digest, Sha256};
+
+    use crate::ecdsa::{Ecdsa, Rfc6979, Signature};
+    use crate::util::buint;
+    use crate::{Curve, ECCError};
+
+    fn hex(s: &str) -> BigUint {
+        BigUint::parse_bytes(s.as_bytes(), 16).unwrap()
+    }
+
+    fn curve_secp256k1() -> Curve {
+        Curve::named("secp256k1").unwrap()
+    }
+
+    fn curve_p256() -> Curve {
+        Curve::named("P-256").unwrap()
+    }
+
+    // ECDSA with the curve's generator and its order
+    fn ecdsa_for(curve: &Curve) -> Ecdsa {
+        Ecdsa::new(
+            curve.generator().unwrap(),
+            curve.subgroup_order().unwrap().clone(),
+        )
+    }
+
+    #[test]
+    fn test_rfc6979_nonce_p256() {
+        // RFC 6979, appendix A.2.5 (ECDSA, 256 Bits (Prime Field)), with SHA-256
+        let curve = curve_p256();
+        let ecdsa = ecdsa_for(&curve);
+        let x = hex("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721");
+
+        let mut nonces = Rfc6979::new(&x, &Sha256::digest(b"sample"), &ecdsa.order);
+        assert_eq!(
+            nonces.next_nonce(),
+            hex("a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60")
+        );
+        let mut nonces = Rfc6979::new(&x, &Sha256::digest(b"test"), &ecdsa.order);
+        assert_eq!(
+            nonces.next_nonce(),
+            hex("d16b6ae827f17175e040871a1c7ec3500192c4c92677336ec2537acaee0008e0")
+        );
+    }
+
+    #[test]
+    fn test_sign_p256() {
+        // RFC 6979, appendix A.2.5. The "sample" signature has a high S: we return n - s.
+        let curve = curve_p256();
+        let ecdsa = ecdsa_for(&curve);
+        let x = hex("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721");
+
+        let public_key = ecdsa.public_key(&x).unwrap();
+        assert_eq!(
+            public_key,
+            curve.point(
+                hex("60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6"),
+                hex("7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299"),
+            )
+        );
+
+        let sample = ecdsa.sign(&x, b"sample").unwrap();
+        assert_eq!(
+            sample.r,
+            hex("efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716")
+        );
+        assert_eq!(
+            sample.s,
+            &ecdsa.order - hex("f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8")
+        );
+        assert!(ecdsa.verify(&public_key, b"sample", &sample));
+
+        let test = ecdsa.sign(&x, b"test").unwrap();
+        assert_eq!(
+            test.r,
+            hex("f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367")
+        );
+        assert_eq!(
+            test.s,
+            hex("019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083")
+        );
+        assert!(ecdsa.verify(&public_key, b"test", &test));
+        assert!(!ecdsa.verify(&public_key, b"sample", &test));
+    }
+
+    #[test]
+    fn test_sign_secp256k1() {
+        // Vectors commonly used by Bitcoin libraries (e.g. python-ecdsa, trezor-crypto)
+        let curve = curve_secp256k1();
+        let ecdsa = ecdsa_for(&curve);
+        let n = ecdsa.order.clone();
+
+        for (private_key, message, r, s) in [
+            (
+                buint(1),
+                "Satoshi Nakamoto",
+                "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8",
+                "2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5",
+            ),
+            (
+                n.clone() - buint(1),
+                "Satoshi Nakamoto",
+                "fd567d121db66e382991534ada77a6bd3106f0a1098c231e47993447cd6af2d0",
+                "6b39cd0eb1bc8603e159ef5c20a5c8ad685a45b06ce9bebed3f153d10d93bed5",
+            ),
+            (
+                hex("f8b8af8ce3c7cca5e300d33939540c10d45ce001b8f252bfbc57ba0342904181"),
+                "Alan Turing",
+                "7063ae83e7f62bbb171798131b4a0564b956930092b33b07b395615d9ec7e15c",
+                "58dfcc1e00a35e1572f366ffe34ba0fc47db1e7189759b9fb233c5b05ab388ea",
+            ),
+        ] {
+            let signature = ecdsa.sign(&private_key, message.as_bytes()).unwrap();
+            assert_eq!(signature.r, hex(r));
+            assert_eq!(signature.s, hex(s));
+            assert!(signature.s <= &n >> 1);
+
+            let public_key = ecdsa.public_key(&private_key).unwrap();
+            assert!(ecdsa.verify(&public_key, message.as_bytes(), &signature));
+            assert_eq!(
+                ecdsa.recover(message.as_bytes(), &signature).unwrap(),
+                public_key
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_signatures() {
+        let curve = curve_secp256k1();
+        let ecdsa = ecdsa_for(&curve);
+        let private_key = buint(0xc0ffeeu32);
+        let public_key = ecdsa.public_key(&private_key).unwrap();
+        let signature = ecdsa.sign(&private_key, b"hello").unwrap();
+        assert!(ecdsa.verify(&public_key, b"hello", &signature));
+
+        let tampered = Signature {
+            s: signature.s.clone() + buint(1),
+            ..signature.clone()
+        };
+        assert!(!ecdsa.verify(&public_key, b"hello", &tampered));
+
+        let zero_r = Signature {
+            r: buint(0),
+            ..signature.clone()
+        };
+        assert!(!ecdsa.verify(&public_key, b"hello", &zero_r));
+
+        let other_key = ecdsa.public_key(&buint(0xdecafu32)).unwrap();
+        assert!(!ecdsa.verify(&other_key, b"hello", &signature));
+
+        // Flipping the parity bit recovers a different key
+        let flipped = Signature {
+            recovery_id: signature.recovery_id ^ 1,
+            ..signature
+        };
+        assert_ne!(ecdsa.recover(b"hello", &flipped).unwrap(), public_key);
+    }
+
+    #[test]
+    fn test_invalid_private_keys() {
+        let curve = curve_secp256k1();
+        let ecdsa = ecdsa_for(&curve);
+        assert!(matches!(
+            ecdsa.sign(&buint(0), b"hello"),
+            Err(ECCError::InvalidScalar)
+        ));
+        assert!(matches!(
+            ecdsa.public_key(&ecdsa.order.clone()),
+            Err(ECCError::InvalidScalar)
+        ));
+    }
+}
@@ -0,0 +1,378 @@
+use std::fmt;
+use std::ops;
+use std::sync::Arc;
+
+use num_bigint::{BigUint, ToBigInt, ToBigUint};
+
+use crate::element::Element;
+use crate::montgomery::{MontgomeryCurve, MontgomeryPoint};
+use crate::util::buint;
+use crate::ECCError;
+
+/// Twisted Edwards curve: a.x^2 + y^2 = 1 + d.x^2.y^2 (mod p)
+///
+/// The addition law below is unified (the same formula handles doubling) and, when `a` is a square
+/// and `d` isn't, complete: there are no exceptional cases, not even the neutral element (0, 1).
+///
+/// Like `Curve`, cheap to clone: points hold a clone of their curve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdwardsCurve {
+    params: Arc<EdwardsParams>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct EdwardsParams {
+    name: String,
+    p: BigUint,
+    a: Element,
+    d: Element,
+}
+
+impl ops::Deref for EdwardsCurve {
+    type Target = EdwardsParams;
+
+    fn deref(&self) -> &EdwardsParams {
+        &self.params
+    }
+}
+
+impl fmt::Display for EdwardsCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<Curve {} (twisted Edwards): {}x^2 + y^2 = 1 + {}x^2y^2 (mod {})>",
+            self.name,
+            self.a.value(),
+            self.d.value(),
+            self.p
+        )
+    }
+}
+
+impl EdwardsCurve {
+    pub fn new<BUINT: ToBigUint, BINT: ToBigInt>(
+        name: &str,
+        a: BINT,
+        d: BINT,
+        p: BUINT,
+    ) -> EdwardsCurve {
+        let prime = buint(p);
+        Self::from_elements(name, Element::new(a, prime.clone()), Element::new(d, prime))
+    }
+
+    /// Same as `new`, for when `a` and `d` are already field elements (e.g. d = -121665/121666).
+    pub fn from_elements(name: &str, a: Element, d: Element) -> EdwardsCurve {
+        EdwardsCurve {
+            params: Arc::new(EdwardsParams {
+                name: name.to_string(),
+                p: a.modulus().clone(),
+                a,
+                d,
+            }),
+        }
+    }
+
+    pub fn contains(&self, point: &EdwardsPoint) -> bool {
+        let x_squared = point.x.pow(2);
+        let y_squared = point.y.pow(2);
+        self.a.clone() * x_squared.clone() + y_squared.clone()
+            == self.element(1) + self.d.clone() * x_squared * y_squared
+    }
+
+    /// The birationally equivalent Montgomery curve B.v^2 = u^3 + A.u^2 + u, with
+    ///     A = 2(a + d) / (a - d)
+    ///     B = 4 / (a - d)
+    pub fn to_montgomery(&self) -> MontgomeryCurve {
+        let a_minus_d = self.a.clone() - self.d.clone();
+        MontgomeryCurve::from_elements(
+            &self.name,
+            self.element(2) * (self.a.clone() + self.d.clone()) / a_minus_d.clone(),
+            self.element(4) / a_minus_d,
+        )
+    }
+
+    pub fn element<T: ToBigInt>(&self, value: T) -> Element {
+        Element::in_field(value, self.a.field())
+    }
+
+    pub fn point<T: ToBigInt>(&self, x: T, y: T) -> EdwardsPoint {
+        EdwardsPoint {
+            x: Element::in_field(x, self.a.field()),
+            y: Element::in_field(y, self.a.field()),
+            curve: self.clone(),
+        }
+    }
+
+    /// The neutral element, (0, 1)
+    pub fn identity(&self) -> EdwardsPoint {
+        self.point(0, 1)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdwardsPoint {
+    x: Element,
+    y: Element,
+    curve: EdwardsCurve,
+}
+
+impl fmt::Display for EdwardsPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<Point on curve {}: x={}, y={}>",
+            self.curve.name,
+            self.x.value(),
+            self.y.value()
+        )
+    }
+}
+
+impl ops::Neg for EdwardsPoint {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: self.y,
+            curve: self.curve,
+        }
+    }
+}
+
+impl ops::Add for EdwardsPoint {
+    type Output = Self;
+
+    /// Unified addition:
+    ///     x3 = (x1.y2 + y1.x2) / (1 + d.x1.x2.y1.y2)
+    ///     y3 = (y1.y2 - a.x1.x2) / (1 - d.x1.x2.y1.y2)
+    fn add(self, other: Self) -> Self {
+        if other.curve != self.curve {
+            panic!("{}", ECCError::CurveMismatch)
+        }
+        let c = self.curve;
+        let x1x2 = self.x.clone() * other.x.clone();
+        let y1y2 = self.y.clone() * other.y.clone();
+        let t = c.d.clone() * x1x2.clone() * y1y2.clone();
+
+        let x = (self.x * other.y + self.y * other.x) / (c.element(1) + t.clone());
+        let y = (y1y2 - c.a.clone() * x1x2) / (c.element(1) - t);
+
+        Self { x, y, curve: c }
+    }
+}
+
+impl EdwardsPoint {
+    pub fn x(&self) -> &Element {
+        &self.x
+    }
+
+    pub fn y(&self) -> &Element {
+        &self.y
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.x.is_zero() && self.y == self.curve.element(1)
+    }
+
+    // Point multiplication, implemented with the double-and-add technique.
+    // Because the addition law is unified, doubling is just P + P.
+    pub fn mul(&self, scalar: BigUint) -> Self {
+        let mut result = self.curve.identity();
+        let mut multiplier = scalar;
+        let mut doubled_point = self.clone();
+        while multiplier != buint(0) {
+            if multiplier.bit(0) {
+                result = result + doubled_point.clone();
+            }
+            doubled_point = doubled_point.clone() + doubled_point;
+            multiplier >>= 1;
+        }
+        result
+    }
+
+    /// Maps this point to the birationally equivalent Montgomery curve (see `EdwardsCurve::to_montgomery`):
+    ///     u = (1 + y) / (1 - y)
+    ///     v = u / x
+    /// The neutral element maps to the point at infinity, for which we return `None`.
+    /// The point of order 2, (0, -1), maps to (0, 0).
+    pub fn to_montgomery(&self) -> Option<MontgomeryPoint> {
+        let c = &self.curve;
+        if self.is_identity() {
+            return None;
+        }
+        if self.x.is_zero() {
+            return Some(MontgomeryPoint::new(c.element(0), c.element(0)));
+        }
+        let u = (c.element(1) + self.y.clone()) / (c.element(1) - self.y.clone());
+        let v = u.clone() / self.x.clone();
+        Some(MontgomeryPoint::new(u, v))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+
+    use crate::edwards::{EdwardsCurve, EdwardsPoint};
+    use crate::element::Element;
+    use crate::util::{bint, buint};
+
+    fn p25519() -> BigUint {
+        buint(bint(2).pow(255) - bint(19))
+    }
+
+    // edwards25519: -x^2 + y^2 = 1 - (121665/121666).x^2.y^2 (mod 2^255 - 19)
+    fn curve_ed25519() -> EdwardsCurve {
+        let p = p25519();
+        let d = Element::new(-121665, p.clone()) / Element::new(121666, p.clone());
+        EdwardsCurve::from_elements("edwards25519", Element::new(-1, p), d)
+    }
+
+    fn dec(s: &[u8]) -> BigUint {
+        BigUint::parse_bytes(s, 10).unwrap()
+    }
+
+    // Ed25519 base point, from RFC 8032, section 5.1
+    fn base_point_coordinates() -> (BigUint, BigUint) {
+        (
+            dec(b"15112221349535400772501151409588531511454012693041857206046113283949847762202"),
+            dec(b"46316835694926478169428394003475163141307993866256225615783033603165251855960"),
+        )
+    }
+
+    #[test]
+    fn test_edwards_display() {
+        let c = EdwardsCurve::new("toy", 1, 2, 13);
+        assert_eq!(
+            format!("{c}"),
+            "<Curve toy (twisted Edwards): 1x^2 + y^2 = 1 + 2x^2y^2 (mod 13)>"
+        );
+    }
+
+    #[test]
+    fn test_group_law_on_toy_edwards_curve() {
+        // x^2 + y^2 = 1 + 2x^2y^2 on F13: a = 1 is a square and 2 isn't, so the addition law is complete.
+        let c = EdwardsCurve::new("toy", 1, 2, 13);
+        let mut points = vec![];
+        for x in 0..13 {
+            for y in 0..13 {
+                let p = c.point(x, y);
+                if c.contains(&p) {
+                    points.push(p);
+                }
+            }
+        }
+        let identity = c.identity();
+        for a in &points {
+            assert_eq!(a.clone() + identity.clone(), a.clone());
+            assert!((a.clone() + -a.clone()).is_identity());
+            for b in &points {
+                let sum = a.clone() + b.clone();
+                assert!(c.contains(&sum));
+                assert_eq!(sum, b.clone() + a.clone());
+                for d in &points {
+                    assert_eq!(
+                        (a.clone() + b.clone()) + d.clone(),
+                        a.clone() + (b.clone() + d.clone())
+                    );
+                }
+            }
+        }
+        for a in &points {
+            assert!(a.mul(buint(points.len())).is_identity());
+        }
+    }
+
+    #[test]
+    fn test_ed25519_base_point_multiples() {
+        let c = curve_ed25519();
+        let (x, y) = base_point_coordinates();
+        let b = c.point(x, y);
+        assert!(c.contains(&b));
+
+        let double = b.mul(buint(2));
+        assert_eq!(double, b.clone() + b.clone());
+        assert_eq!(
+            double.x.value(),
+            dec(b"24727413235106541002554574571675588834622768167397638456726423682521233608206")
+        );
+        assert_eq!(
+            double.y.value(),
+            dec(b"15549675580280190176352668710449542251549572066445060580507079593062643049417")
+        );
+
+        let triple = b.mul(buint(3));
+        assert_eq!(
+            triple.x.value(),
+            dec(b"46896733464454938657123544595386787789046198280132665686241321779790909858396")
+        );
+        assert_eq!(
+            triple.y.value(),
+            dec(b"8324843778533443976490377120369201138301417226297555316741202210403726505172")
+        );
+
+        let quintuple = b.mul(buint(5));
+        assert_eq!(
+            quintuple.x.value(),
+            dec(b"33467004535436536005251147249499675200073690106659565782908757308821616914995")
+        );
+        assert_eq!(
+            quintuple.y.value(),
+            dec(b"43097193783671926753355113395909008640284023746042808659097434958891230611693")
+        );
+
+        // The base point has prime order l = 2^252 + 27742317777372353535851937790883648493
+        let l = buint(bint(2).pow(252) + bint(dec(b"27742317777372353535851937790883648493")));
+        assert!(b.mul(l).is_identity());
+    }
+
+    #[test]
+    fn test_ed25519_maps_to_curve25519() {
+        let c = curve_ed25519();
+        let montgomery = c.to_montgomery();
+        // Curve25519 has A = 486662. We get B = -486664 rather than B = 1: the two curves are
+        // isomorphic over F_p, since -486664 is a square mod 2^255 - 19.
+        assert_eq!(montgomery.a().clone(), c.element(486662));
+        assert_eq!(montgomery.b().clone(), c.element(-486664));
+
+        // The Ed25519 base point maps to the X25519 base point, u = 9
+        let (x, y) = base_point_coordinates();
+        let b = c.point(x, y);
+        let mapped = b.to_montgomery().unwrap();
+        assert_eq!(mapped.u().clone(), c.element(9));
+        assert!(montgomery.contains(&mapped));
+
+        // ...and back
+        let edwards = montgomery.to_edwards();
+        let back = montgomery.to_edwards_point(&edwards, &mapped).unwrap();
+        assert_eq!(back.x, b.x);
+        assert_eq!(back.y, b.y);
+    }
+
+    #[test]
+    fn test_point_outlives_curve() {
+        // Points own a handle on their curve, so they can be returned from where the curve was built
+        fn base_point() -> EdwardsPoint {
+            let (x, y) = base_point_coordinates();
+            curve_ed25519().point(x, y)
+        }
+        let b = base_point();
+        assert_eq!(b.clone() + b.clone(), b.mul(buint(2)));
+        assert_eq!(
+            format!("{}", b.curve.to_montgomery().to_edwards()),
+            format!("{}", b.curve)
+        );
+    }
+
+    #[test]
+    fn test_special_points_to_montgomery() {
+        let c = curve_ed25519();
+        assert!(c.identity().to_montgomery().is_none());
+
+        let order_two = c.point(0, -1);
+        assert!(c.contains(&order_two));
+        let mapped = order_two.to_montgomery().unwrap();
+        assert!(mapped.u().is_zero());
+        assert!(mapped.v().is_zero());
+    }
+}
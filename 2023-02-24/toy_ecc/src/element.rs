@@ -1,16 +1,162 @@
-use std::fmt::Display;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
 use std::ops;
+use std::sync::Arc;
 
 use num_bigint::{BigUint, ToBigInt, ToBigUint};
 
 use crate::eea;
 use crate::util::{bint, buint};
+use crate::ECCError;
+
+/// Fields created by `Element::new` are kept around, so that elements created separately share them
+const FIELD_CACHE_SIZE: usize = 64;
+
+/// Exponents up to this size (the squares and cubes of the curve formulas) are computed in Montgomery form.
+/// Larger ones go through BigUint::modpow.
+const SMALL_EXPONENT_BITS: u64 = 8;
+
+thread_local! {
+    static FIELDS: RefCell<HashMap<BigUint, Arc<Field>>> = RefCell::new(HashMap::new());
+}
+
+/// Everything needed to compute modulo `modulus`, shared by all the elements of a field.
+///
+/// For odd moduli, elements are kept in Montgomery form: x is stored as x.R mod modulus, with
+/// R = 2^r_bits > modulus. A product x.R * y.R is then brought back to (x.y).R with a Montgomery
+/// reduction, which only needs multiplications, masks and shifts instead of a division by the modulus.
+/// Additions and subtractions work on Montgomery forms as-is.
+/// Even moduli (which do show up, e.g. in CRT computations) fall back to regular reductions.
+#[derive(Debug)]
+pub struct Field {
+    modulus: BigUint,
+    montgomery: Option<MontgomeryParams>,
+}
+
+#[derive(Debug)]
+struct MontgomeryParams {
+    r_bits: u64,
+    // R - 1, to compute x mod R
+    r_mask: BigUint,
+    // R^2 mod modulus, to convert into Montgomery form
+    r2: BigUint,
+    // n' = -modulus^-1 mod R
+    n_prime: BigUint,
+}
+
+impl Field {
+    pub fn new<T: ToBigUint>(modulus: T) -> Arc<Self> {
+        let modulus = buint(modulus);
+        let montgomery = if modulus.bit(0) {
+            Some(MontgomeryParams::new(&modulus))
+        } else {
+            None
+        };
+        Arc::new(Self {
+            modulus,
+            montgomery,
+        })
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    fn cached(modulus: BigUint) -> Arc<Self> {
+        FIELDS.with(|fields| {
+            let mut fields = fields.borrow_mut();
+            if let Some(field) = fields.get(&modulus) {
+                return field.clone();
+            }
+            if fields.len() >= FIELD_CACHE_SIZE {
+                fields.clear();
+            }
+            let field = Field::new(modulus.clone());
+            fields.insert(modulus, field.clone());
+            field
+        })
+    }
+
+    // For a value in [0, modulus[
+    fn montgomery_form(&self, value: BigUint) -> BigUint {
+        match &self.montgomery {
+            Some(params) => self.redc(value * &params.r2),
+            None => value,
+        }
+    }
+
+    fn standard_form(&self, value: &BigUint) -> BigUint {
+        match &self.montgomery {
+            Some(_) => self.redc(value.clone()),
+            None => value.clone(),
+        }
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        match &self.montgomery {
+            Some(_) => self.redc(a * b),
+            None => (a * b) % &self.modulus,
+        }
+    }
+
+    // Montgomery reduction: t.R^-1 mod modulus, for t < modulus.R.
+    // Adding q.modulus, with q = t.n' mod R, makes t divisible by R without changing it mod modulus.
+    fn redc(&self, t: BigUint) -> BigUint {
+        let params = self.montgomery.as_ref().unwrap();
+        let q = ((&t & &params.r_mask) * &params.n_prime) & &params.r_mask;
+        let reduced = (t + q * &self.modulus) >> params.r_bits;
+        if reduced >= self.modulus {
+            reduced - &self.modulus
+        } else {
+            reduced
+        }
+    }
+}
+
+// Fields are the same if they have the same modulus, even if they were created separately
+impl PartialEq for Field {
+    fn eq(&self, other: &Self) -> bool {
+        self.modulus == other.modulus
+    }
+}
+
+impl Eq for Field {}
+
+impl MontgomeryParams {
+    fn new(modulus: &BigUint) -> Self {
+        // R is a whole number of 64-bit limbs
+        let r_bits = modulus.bits().div_ceil(64).max(1) * 64;
+        let r = buint(1) << r_bits;
+        let r_mask = &r - buint(1);
+        let r2 = (&r * &r) % modulus;
+
+        // Newton's iteration for modulus^-1 mod R: inverse = inverse * (2 - modulus * inverse).
+        // Each step doubles the number of correct low bits, and any odd modulus is its own inverse mod 2.
+        let mut inverse = buint(1);
+        let mut correct_bits = 1;
+        while correct_bits < r_bits {
+            let error = (modulus * &inverse) & &r_mask;
+            inverse = (&inverse * (&r + buint(2) - error)) & &r_mask;
+            correct_bits *= 2;
+        }
+        let n_prime = (&r - inverse) & &r_mask;
+
+        Self {
+            r_bits,
+            r_mask,
+            r2,
+            n_prime,
+        }
+    }
+}
 
 // Type to wrap BigUint and do math in a finite field
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct Element {
-    pub value: BigUint,
-    pub modulus: BigUint,
+    // In Montgomery form, when the field has an odd modulus
+    value: BigUint,
+    field: Arc<Field>,
 }
 
 impl Element {
@@ -18,48 +164,223 @@ impl Element {
     /// If the value passed in is negative, we set the Element to be (modulus - value)
     /// If the value passed in is more than the modulus, we reduce it to be in the range [0, modulus[
     pub fn new<BINT: ToBigInt, BUINT: ToBigUint>(value: BINT, modulus: BUINT) -> Self {
-        let m = buint(modulus);
-        let v = bint(value);
+        Self::in_field(value, &Field::cached(buint(modulus)))
+    }
 
-        let val = if v < bint(0) {
-            buint(bint(m.clone()) + v)
-        } else if v >= bint(m.clone()) {
-            buint(v.modpow(&bint(1), &bint(m.clone())))
-        } else {
-            buint(v)
-        };
+    /// Same as `new`, with an existing field
+    pub fn in_field<T: ToBigInt>(value: T, field: &Arc<Field>) -> Self {
+        let m = bint(field.modulus.clone());
+        let v = bint(value) % &m;
+        let v = if v < bint(0) { v + m } else { v };
+        Self::from_reduced(buint(v), field)
+    }
 
+    // For a value in [0, modulus[
+    fn from_reduced(value: BigUint, field: &Arc<Field>) -> Self {
         Self {
-            value: val,
-            modulus: m,
+            value: field.montgomery_form(value),
+            field: field.clone(),
         }
     }
 
+    pub fn value(&self) -> BigUint {
+        self.field.standard_form(&self.value)
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.field.modulus
+    }
+
+    pub fn field(&self) -> &Arc<Field> {
+        &self.field
+    }
+
     pub fn pow(&self, exponent: usize) -> Self {
-        Self {
-            value: self.value.modpow(&buint(exponent), &self.modulus),
-            modulus: self.modulus.clone(),
+        self.pow_big(&buint(exponent))
+    }
+
+    pub fn pow_big(&self, exponent: &BigUint) -> Self {
+        if exponent.bits() > SMALL_EXPONENT_BITS {
+            return Self::from_reduced(self.value().modpow(exponent, self.modulus()), &self.field);
+        }
+        // Square-and-multiply, without leaving Montgomery form
+        let mut result = Self::in_field(1, &self.field);
+        for i in (0..exponent.bits()).rev() {
+            result = result.with_value(self.field.mul(&result.value, &result.value));
+            if exponent.bit(i) {
+                result = result.with_value(self.field.mul(&result.value, &self.value));
+            }
         }
+        result
     }
 
     pub fn is_zero(&self) -> bool {
+        // 0 is its own Montgomery form
         self.value == buint(0)
     }
+
+    /// Same as `+`, but returns an error instead of panicking when the moduli differ
+    pub fn checked_add(&self, other: &Self) -> Result<Self, ECCError> {
+        self.check_modulus(other)?;
+        let sum = &self.value + &other.value;
+        let value = if &sum >= self.modulus() {
+            sum - self.modulus()
+        } else {
+            sum
+        };
+        Ok(self.with_value(value))
+    }
+
+    /// Same as `-`, but returns an error instead of panicking when the moduli differ
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, ECCError> {
+        self.check_modulus(other)?;
+        let value = if self.value >= other.value {
+            &self.value - &other.value
+        } else {
+            self.modulus() + &self.value - &other.value
+        };
+        Ok(self.with_value(value))
+    }
+
+    /// Same as `*`, but returns an error instead of panicking when the moduli differ
+    pub fn checked_mul(&self, other: &Self) -> Result<Self, ECCError> {
+        self.check_modulus(other)?;
+        Ok(self.with_value(self.field.mul(&self.value, &other.value)))
+    }
+
+    /// Same as `/`, but returns an error instead of panicking when the moduli differ,
+    /// or when `other` isn't invertible
+    pub fn checked_div(&self, other: &Self) -> Result<Self, ECCError> {
+        self.check_modulus(other)?;
+        self.checked_mul(&other.inverse()?)
+    }
+
+    /// Multiplicative inverse, if GCD(value, modulus) = 1
+    pub fn inverse(&self) -> Result<Self, ECCError> {
+        // EEA gives: value*u + modulus*v = GCD(value, modulus).
+        // When the GCD is 1, `u` is an inverse of `value` mod `modulus`.
+        let eea_out =
+            eea::eea(self.modulus().clone(), self.value()).map_err(|_| ECCError::NotInvertible)?;
+        if eea_out.gcd != buint(1) {
+            return Err(ECCError::NotInvertible);
+        }
+        // `u` could be negative: `in_field` takes care of it
+        Ok(Self::in_field(eea_out.u, &self.field))
+    }
+
+    // Element of the same field, from a value already in Montgomery form
+    fn with_value(&self, value: BigUint) -> Self {
+        Self {
+            value,
+            field: self.field.clone(),
+        }
+    }
+
+    fn check_modulus(&self, other: &Self) -> Result<(), ECCError> {
+        if self.field != other.field {
+            return Err(ECCError::ModulusMismatch);
+        }
+        Ok(())
+    }
+
+    /// Legendre symbol, through Euler's criterion: 1 if self is a non-zero square, -1 if it isn't a square, 0 if it's zero
+    pub fn legendre(&self) -> i8 {
+        if self.is_zero() {
+            return 0;
+        }
+        let p = self.modulus();
+        if self.pow_big(&((p - buint(1)) >> 1)) == Self::in_field(1, &self.field) {
+            1
+        } else {
+            -1
+        }
+    }
+
+    /// One of the square roots of self, if it's a square (the other one is its opposite).
+    /// Uses the p = 3 (mod 4) shortcut when possible, Tonelli-Shanks otherwise.
+    /// Meant for a prime p: other moduli may get None for a square, but never a wrong root, and
+    /// never loop forever.
+    pub fn sqrt(&self) -> Option<Self> {
+        let p = self.modulus();
+        if self.is_zero() || *p == buint(2) {
+            // In F_2, 0^2 = 0 and 1^2 = 1
+            return Some(self.clone());
+        }
+        if self.legendre() != 1 {
+            return None;
+        }
+        if p % buint(4) == buint(3) {
+            // self^((p+1)/4) squared is self^((p+1)/2) = self * self^((p-1)/2) = self
+            let root = self.pow_big(&((p + buint(1)) >> 2));
+            // ... if p is prime
+            return (root.pow(2) == *self).then_some(root);
+        }
+
+        // Tonelli-Shanks: p - 1 = q * 2^s, with q odd
+        let s = (p - buint(1)).trailing_zeros().unwrap();
+        let q = (p - buint(1)) >> s;
+
+        // Any non-square z works. Half of F_p* are, but there's none if p isn't prime.
+        let one = Self::in_field(1, &self.field);
+        let mut z = Self::in_field(2, &self.field);
+        while z.legendre() != -1 {
+            z = z + one.clone();
+            if z.is_zero() {
+                return None;
+            }
+        }
+
+        let mut m = s;
+        let mut c = z.pow_big(&q);
+        let mut t = self.pow_big(&q);
+        let mut root = self.pow_big(&((&q + buint(1)) >> 1));
+
+        while t != one {
+            // Find the least i such that t^(2^i) = 1
+            let mut i = 0;
+            let mut t_pow = t.clone();
+            while t_pow != one {
+                t_pow = t_pow.pow(2);
+                i += 1;
+                if i == m {
+                    // Only possible if p isn't prime
+                    return None;
+                }
+            }
+            let b = c.pow_big(&(buint(1) << (m - i - 1)));
+            m = i;
+            c = b.pow(2);
+            t = t * c.clone();
+            root = root * b;
+        }
+        Some(root)
+    }
 }
 
-impl Display for Element {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} (mod {})", self.value, self.modulus)
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (mod {})", self.value(), self.modulus())
+    }
+}
+
+// Shows the actual value, not its Montgomery form
+impl fmt::Debug for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Element")
+            .field("value", &self.value())
+            .field("modulus", self.modulus())
+            .finish()
     }
 }
 
 impl ops::Neg for Element {
     type Output = Self;
     fn neg(self) -> Self::Output {
-        Self {
-            value: self.modulus.clone() - self.value,
-            modulus: self.modulus,
+        if self.is_zero() {
+            return self;
         }
+        let value = self.modulus() - &self.value;
+        self.with_value(value)
     }
 }
 
@@ -67,14 +388,7 @@ impl ops::Add for Element {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        if self.modulus != other.modulus {
-            panic!("Cannot add elements with different modulus");
-        }
-
-        Self {
-            value: (self.value + other.value) % self.modulus.clone(),
-            modulus: self.modulus,
-        }
+        self.checked_add(&other).unwrap_or_else(|e| panic!("{e}"))
     }
 }
 
@@ -82,19 +396,7 @@ impl ops::Sub for Element {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        if self.modulus != other.modulus {
-            panic!("Cannot subtract elements with different modulus");
-        }
-        let value = if self.value >= other.value {
-            self.value - other.value
-        } else {
-            self.modulus.clone() + self.value - other.value
-        };
-
-        Self {
-            value: value % self.modulus.clone(),
-            modulus: self.modulus,
-        }
+        self.checked_sub(&other).unwrap_or_else(|e| panic!("{e}"))
     }
 }
 
@@ -102,14 +404,7 @@ impl ops::Mul for Element {
     type Output = Self;
 
     fn mul(self, other: Self) -> Self {
-        if self.modulus != other.modulus {
-            panic!("Cannot multiply elements with different modulus");
-        }
-
-        Self {
-            value: (self.value * other.value) % self.modulus.clone(),
-            modulus: self.modulus,
-        }
+        self.checked_mul(&other).unwrap_or_else(|e| panic!("{e}"))
     }
 }
 
@@ -117,38 +412,88 @@ impl ops::Div for Element {
     type Output = Self;
 
     fn div(self, other: Self) -> Self {
-        let res = eea::eea(other.modulus.clone(), other.value.clone());
-        if res.is_err() {
-            panic!("error while computing EEA: {res:?}");
-        }
+        self.checked_div(&other).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
 
-        let eea_out = res.unwrap();
-        if eea_out.gcd != buint(1) {
-            panic!(
-                "cannot inverse because GCD({}, {}) != 1",
-                &other, &other.modulus
-            )
-        }
+/// What curves and points need from their coordinates. Implemented by `Element` (F_p), and by
+/// `ExtElement` (F_p^k, see the `extension` module).
+pub trait FieldElement:
+    Clone
+    + PartialEq
+    + Eq
+    + fmt::Debug
+    + ops::Add<Output = Self>
+    + ops::Sub<Output = Self>
+    + ops::Mul<Output = Self>
+    + ops::Div<Output = Self>
+    + ops::Neg<Output = Self>
+{
+    /// An integer, as an element of the same field as self
+    fn embed<T: ToBigInt>(&self, value: T) -> Self;
+    /// p, for a field with p^k elements
+    fn characteristic(&self) -> &BigUint;
+    /// p^k, the number of elements in the field
+    fn field_order(&self) -> BigUint;
+    fn is_zero(&self) -> bool;
+    fn pow(&self, exponent: usize) -> Self;
+    fn pow_big(&self, exponent: &BigUint) -> Self;
+    fn checked_add(&self, other: &Self) -> Result<Self, ECCError>;
+    fn checked_sub(&self, other: &Self) -> Result<Self, ECCError>;
+    fn checked_mul(&self, other: &Self) -> Result<Self, ECCError>;
+    fn checked_div(&self, other: &Self) -> Result<Self, ECCError>;
+}
 
-        // EEA gives: other*u + modulus*v = 1.
-        // So `u` is an inverse of `other` mod `modulus`.
-        // However, `u` could be negative (in this case, return u + modulus)
-        let other_inv = if eea_out.u < bint(0) {
-            buint(eea_out.u + bint(self.modulus.clone()))
-        } else {
-            buint(eea_out.u) % self.modulus.clone()
-        };
+impl FieldElement for Element {
+    fn embed<T: ToBigInt>(&self, value: T) -> Self {
+        Self::in_field(value, &self.field)
+    }
 
-        Self {
-            value: (self.value * other_inv) % self.modulus.clone(),
-            modulus: self.modulus,
-        }
+    fn characteristic(&self) -> &BigUint {
+        self.modulus()
+    }
+
+    fn field_order(&self) -> BigUint {
+        self.modulus().clone()
+    }
+
+    fn is_zero(&self) -> bool {
+        Element::is_zero(self)
+    }
+
+    fn pow(&self, exponent: usize) -> Self {
+        Element::pow(self, exponent)
+    }
+
+    fn pow_big(&self, exponent: &BigUint) -> Self {
+        Element::pow_big(self, exponent)
+    }
+
+    fn checked_add(&self, other: &Self) -> Result<Self, ECCError> {
+        Element::checked_add(self, other)
+    }
+
+    fn checked_sub(&self, other: &Self) -> Result<Self, ECCError> {
+        Element::checked_sub(self, other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Result<Self, ECCError> {
+        Element::checked_mul(self, other)
+    }
+
+    fn checked_div(&self, other: &Self) -> Result<Self, ECCError> {
+        Element::checked_div(self, other)
     }
 }
 
 #[cfg(test)]
 mod test {
+    use num_bigint::BigUint;
+    use proptest::prelude::*;
+
     use crate::element::Element;
+    use crate::util::{buint, gcd};
+    use crate::ECCError;
 
     #[test]
     fn test_display_element() {
@@ -205,4 +550,130 @@ mod test {
         let b = Element::new(9, 13);
         assert_eq!(a / b, Element::new(2, 13));
     }
+
+    #[test]
+    fn test_legendre() {
+        // Squares mod 13: 1, 4, 9, 3, 12, 10
+        let squares = [1, 3, 4, 9, 10, 12];
+        for i in 1..13 {
+            let expected = if squares.contains(&i) { 1 } else { -1 };
+            assert_eq!(Element::new(i, 13).legendre(), expected);
+        }
+        assert_eq!(Element::new(0, 13).legendre(), 0);
+    }
+
+    #[test]
+    fn test_sqrt() {
+        // 19 = 3 (mod 4), 13 = 5 (mod 8), 17 = 1 (mod 16): both code paths, and several Tonelli-Shanks rounds
+        for p in [19, 13, 17, 10009] {
+            for i in 0..p {
+                let a = Element::new(i, p);
+                match a.sqrt() {
+                    Some(root) => assert_eq!(root.pow(2), a),
+                    None => assert_eq!(a.legendre(), -1),
+                }
+            }
+        }
+
+        // p = 2: every element is its own square root
+        assert_eq!(Element::new(0, 2).sqrt(), Some(Element::new(0, 2)));
+        assert_eq!(Element::new(1, 2).sqrt(), Some(Element::new(1, 2)));
+        // Composite moduli don't hang, or give wrong roots: 21 and 25 are 1 (mod 4), 15 is 3
+        for n in [21, 25, 15] {
+            for i in 0..n {
+                if let Some(root) = Element::new(i, n).sqrt() {
+                    assert_eq!(root.pow(2), Element::new(i, n));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_inverse() {
+        assert_eq!(Element::new(3, 13).inverse().unwrap(), Element::new(9, 13));
+        assert!(matches!(
+            Element::new(0, 13).inverse(),
+            Err(ECCError::NotInvertible)
+        ));
+        assert!(matches!(
+            Element::new(4, 12).inverse(),
+            Err(ECCError::NotInvertible)
+        ));
+    }
+
+    #[test]
+    fn test_checked_ops_errors() {
+        let a = Element::new(5, 13);
+        let b = Element::new(5, 17);
+        assert!(matches!(a.checked_add(&b), Err(ECCError::ModulusMismatch)));
+        assert!(matches!(a.checked_sub(&b), Err(ECCError::ModulusMismatch)));
+        assert!(matches!(a.checked_mul(&b), Err(ECCError::ModulusMismatch)));
+        assert!(matches!(a.checked_div(&b), Err(ECCError::ModulusMismatch)));
+        assert!(matches!(
+            a.checked_div(&Element::new(0, 13)),
+            Err(ECCError::NotInvertible)
+        ));
+    }
+
+    proptest! {
+        // The checked API never panics, whatever the values and moduli: it fails exactly when the
+        // operators would panic, and agrees with them otherwise.
+        #[test]
+        fn test_fuzz_checked_ops(
+            a in any::<u64>(),
+            b in any::<u64>(),
+            m1 in 1u64..,
+            m2 in 1u64..,
+            same_modulus in any::<bool>(),
+        ) {
+            let m2 = if same_modulus { m1 } else { m2 };
+            let x = Element::new(a, m1);
+            let y = Element::new(b, m2);
+
+            prop_assert_eq!(x.checked_add(&y).is_ok(), m1 == m2);
+            prop_assert_eq!(x.checked_sub(&y).is_ok(), m1 == m2);
+            prop_assert_eq!(x.checked_mul(&y).is_ok(), m1 == m2);
+            if m1 == m2 {
+                prop_assert_eq!(x.checked_add(&y).unwrap(), x.clone() + y.clone());
+                prop_assert_eq!(x.checked_sub(&y).unwrap(), x.clone() - y.clone());
+                prop_assert_eq!(x.checked_mul(&y).unwrap(), x.clone() * y.clone());
+            }
+
+            let invertible = gcd(&y.value(), &buint(m2)) == buint(1) && !y.is_zero();
+            match x.checked_div(&y) {
+                Ok(quotient) => {
+                    prop_assert!(m1 == m2 && invertible);
+                    prop_assert_eq!(quotient.checked_mul(&y).unwrap(), x.clone());
+                    prop_assert_eq!(quotient, x / y);
+                }
+                Err(ECCError::ModulusMismatch) => prop_assert!(m1 != m2),
+                Err(ECCError::NotInvertible) => prop_assert!(m1 == m2 && !invertible),
+                Err(e) => prop_assert!(false, "unexpected error: {}", e),
+            }
+        }
+
+        // Montgomery arithmetic on multi-limb moduli agrees with plain BigUint arithmetic
+        #[test]
+        fn test_fuzz_montgomery_matches_biguint(
+            a in any::<[u64; 4]>(),
+            b in any::<[u64; 4]>(),
+            exponent in 0usize..300,
+        ) {
+            let to_biguint = |limbs: [u64; 4]| {
+                limbs.iter().rev().fold(buint(0), |acc, &limb| (acc << 64) + buint(limb))
+            };
+            // secp256k1's p, and a 4-limb even modulus
+            let p: BigUint = (buint(1) << 256) - (buint(1) << 32) - buint(977);
+            for modulus in [p.clone(), p - buint(1)] {
+                let (a, b) = (to_biguint(a) % &modulus, to_biguint(b) % &modulus);
+                let x = Element::new(a.clone(), modulus.clone());
+                let y = Element::new(b.clone(), modulus.clone());
+                prop_assert_eq!(x.value(), a.clone());
+                prop_assert_eq!((x.clone() + y.clone()).value(), (&a + &b) % &modulus);
+                prop_assert_eq!((x.clone() - y.clone()).value(), (&modulus + &a - &b) % &modulus);
+                prop_assert_eq!((x.clone() * y.clone()).value(), (&a * &b) % &modulus);
+                prop_assert_eq!(x.pow(exponent).value(), a.modpow(&buint(exponent), &modulus));
+            }
+        }
+    }
 }
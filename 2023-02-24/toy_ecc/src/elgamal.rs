@@ -0,0 +1,318 @@
+//! ElGamal encryption on an elliptic curve: the same scheme as over Z/pZ (see the 2023-02-10
+//! homework), with the group written additively.
+//!   - Bob's key pair is b and B = [b]G
+//!   - to encrypt a point M, Alice picks a random k, and sends (C1, C2) = ([k]G, M + [k]B)
+//!   - Bob recovers M = C2 - [b]C1, since [b]C1 = [b.k]G = [k]B
+//!
+//! Messages have to be points first. Two ways to get there:
+//!   - Koblitz's encoding: m becomes the first point with x = m.K + j, for 0 <= j < K. Half of the
+//!     x-coordinates are on the curve, so all K tries fail with probability about 2^-K. Decoding
+//!     is a division: m = x / K.
+//!   - "exponential" ElGamal: m becomes [m]G. Adding two ciphertexts then adds their messages,
+//!     but decoding [m]G is a discrete logarithm: this only works for small m (with BSGS here).
+
+use std::ops;
+
+use num_bigint::BigUint;
+use rand_core::{CryptoRng, RngCore};
+
+use crate::ecdlp::baby_step_giant_step;
+use crate::util::{bint, buint};
+use crate::{Curve, ECCError, Point};
+
+/// Number of x-coordinates Koblitz's encoding tries for each message
+pub const KOBLITZ_TRIES: u32 = 100;
+
+/// (C1, C2) = ([k]G, M + [k]B)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ciphertext {
+    pub c1: Point,
+    pub c2: Point,
+}
+
+/// Componentwise addition: ([k1]G, M1 + [k1]B) + ([k2]G, M2 + [k2]B) encrypts M1 + M2, with k1 + k2.
+impl ops::Add for Ciphertext {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            c1: self.c1 + other.c1,
+            c2: self.c2 + other.c2,
+        }
+    }
+}
+
+/// ElGamal over any curve, given a base point and its (prime) order.
+#[derive(Debug, Clone)]
+pub struct ElGamal {
+    generator: Point,
+    order: BigUint,
+}
+
+impl ElGamal {
+    pub fn new(generator: Point, order: BigUint) -> Self {
+        Self { generator, order }
+    }
+
+    pub fn public_key(&self, private_key: &BigUint) -> Result<Point, ECCError> {
+        self.check_scalar(private_key)?;
+        self.generator.mul_ct(private_key, &self.order)
+    }
+
+    /// Encrypts a point, with a fresh random k.
+    pub fn encrypt_point<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        public_key: &Point,
+        message: &Point,
+    ) -> Result<Ciphertext, ECCError> {
+        let curve = &self.generator.curve;
+        curve.validate_public_key(public_key, &self.order)?;
+        if message.curve != *curve {
+            return Err(ECCError::CurveMismatch);
+        }
+        let k = random_scalar(rng, &self.order);
+        Ok(Ciphertext {
+            c1: self.generator.mul_ct(&k, &self.order)?,
+            c2: message.checked_add(&public_key.mul_ct(&k, &self.order)?)?,
+        })
+    }
+
+    pub fn decrypt_point(
+        &self,
+        private_key: &BigUint,
+        ciphertext: &Ciphertext,
+    ) -> Result<Point, ECCError> {
+        self.check_scalar(private_key)?;
+        let curve = &self.generator.curve;
+        if !curve.contains(ciphertext.c1.clone()) || !curve.contains(ciphertext.c2.clone()) {
+            return Err(ECCError::PointNotOnCurve);
+        }
+        let shared = ciphertext.c1.mul_ct(private_key, &self.order)?;
+        ciphertext.c2.checked_add(&-shared)
+    }
+
+    /// Encrypts a number, mapped to a point with Koblitz's encoding.
+    pub fn encrypt<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        public_key: &Point,
+        message: &BigUint,
+    ) -> Result<Ciphertext, ECCError> {
+        let point = koblitz_encode(&self.generator.curve, message)?;
+        self.encrypt_point(rng, public_key, &point)
+    }
+
+    pub fn decrypt(
+        &self,
+        private_key: &BigUint,
+        ciphertext: &Ciphertext,
+    ) -> Result<BigUint, ECCError> {
+        let point = self.decrypt_point(private_key, ciphertext)?;
+        koblitz_decode(&point)
+    }
+
+    /// Encrypts [message]G: ciphertexts can be added, and only small sums can be decrypted.
+    pub fn encrypt_exponential<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        public_key: &Point,
+        message: &BigUint,
+    ) -> Result<Ciphertext, ECCError> {
+        let point = self.generator.mul(message % &self.order);
+        self.encrypt_point(rng, public_key, &point)
+    }
+
+    /// Decrypts [m]G, then finds m <= max with baby-step giant-step: about 2.sqrt(max) additions.
+    /// Errors with `NoDiscreteLog` if m > max.
+    pub fn decrypt_exponential(
+        &self,
+        private_key: &BigUint,
+        ciphertext: &Ciphertext,
+        max: &BigUint,
+    ) -> Result<BigUint, ECCError> {
+        let point = self.decrypt_point(private_key, ciphertext)?;
+        // BSGS over [0, max] finds every m <= max, but reduces the others mod (max + 1)
+        let m = baby_step_giant_step(&self.generator, &point, &(max + buint(1)))?.k;
+        if self.generator.mul(m.clone()) != point {
+            return Err(ECCError::NoDiscreteLog);
+        }
+        Ok(m)
+    }
+
+    fn check_scalar(&self, scalar: &BigUint) -> Result<(), ECCError> {
+        if scalar == &buint(0) || scalar >= &self.order {
+            return Err(ECCError::InvalidScalar);
+        }
+        Ok(())
+    }
+}
+
+/// Koblitz's encoding: the first point with x = m.K + j, 0 <= j < K (K = `KOBLITZ_TRIES`).
+/// Messages must satisfy (m + 1).K <= p. Fails with probability about 2^-K.
+pub fn koblitz_encode(curve: &Curve, message: &BigUint) -> Result<Point, ECCError> {
+    let base = message * buint(KOBLITZ_TRIES);
+    if base.clone() + buint(KOBLITZ_TRIES) > curve.p {
+        return Err(ECCError::MessageTooLarge);
+    }
+    for j in 0..KOBLITZ_TRIES {
+        let x = curve.element(bint(base.clone() + buint(j)));
+        if let Some(y) = curve.rhs(&x).sqrt() {
+            return curve.point_from_elements(x, y);
+        }
+    }
+    Err(ECCError::EncodingFailed)
+}
+
+/// m = floor(x / K)
+pub fn koblitz_decode(point: &Point) -> Result<BigUint, ECCError> {
+    if point.is_at_infinity() {
+        return Err(ECCError::PointAtInfinity);
+    }
+    Ok(point.x.value() / buint(KOBLITZ_TRIES))
+}
+
+// Uniform in [1, n - 1], by rejection sampling
+fn random_scalar<R: RngCore + CryptoRng>(rng: &mut R, n: &BigUint) -> BigUint {
+    let bits = n.bits();
+    let mut bytes = vec![0u8; bits.div_ceil(8) as usize];
+    loop {
+        rng.fill_bytes(&mut bytes);
+        let k = BigUint::from_bytes_be(&bytes) >> (bytes.len() as u64 * 8 - bits);
+        if k != buint(0) && &k < n {
+            return k;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    use crate::elgamal::{koblitz_decode, koblitz_encode, ElGamal, KOBLITZ_TRIES};
+    use crate::util::buint;
+    use crate::{Curve, ECCError};
+
+    fn secp256k1_elgamal() -> ElGamal {
+        let curve = Curve::named("secp256k1").unwrap();
+        ElGamal::new(
+            curve.generator().unwrap(),
+            curve.subgroup_order().unwrap().clone(),
+        )
+    }
+
+    #[test]
+    fn test_koblitz_encoding() {
+        let curve = Curve::named("secp256k1").unwrap();
+        for m in [
+            buint(0),
+            buint(12),
+            BigUint::from_bytes_be(b"attack at dawn"),
+        ] {
+            let point = koblitz_encode(&curve, &m).unwrap();
+            assert!(curve.contains(point.clone()));
+            assert_eq!(koblitz_decode(&point).unwrap(), m);
+        }
+
+        // Y^2 = X^3 + 30X + 34 on F631: room for 6 messages, all encodable
+        let toy = Curve::named("itmc-631").unwrap();
+        for m in 0u32..6 {
+            let point = koblitz_encode(&toy, &buint(m)).unwrap();
+            assert_eq!(koblitz_decode(&point).unwrap(), buint(m));
+        }
+        assert!(matches!(
+            koblitz_encode(&toy, &buint(631 / KOBLITZ_TRIES)),
+            Err(ECCError::MessageTooLarge)
+        ));
+    }
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let mut rng = ChaCha20Rng::seed_from_u64(1);
+        let elgamal = secp256k1_elgamal();
+        let bob = buint(5);
+        let public_key = elgamal.public_key(&bob).unwrap();
+
+        let message = BigUint::from_bytes_be(b"m = 12, as in the homework");
+        let ciphertext = elgamal.encrypt(&mut rng, &public_key, &message).unwrap();
+        assert_eq!(elgamal.decrypt(&bob, &ciphertext).unwrap(), message);
+
+        // Encryption is randomized
+        let again = elgamal.encrypt(&mut rng, &public_key, &message).unwrap();
+        assert_ne!(ciphertext, again);
+        assert_eq!(elgamal.decrypt(&bob, &again).unwrap(), message);
+
+        // The wrong key gives the wrong point
+        let eve = buint(6);
+        assert_ne!(elgamal.decrypt(&eve, &ciphertext).ok(), Some(message));
+    }
+
+    #[test]
+    fn test_exponential_elgamal_is_homomorphic() {
+        let mut rng = ChaCha20Rng::seed_from_u64(2);
+        let elgamal = secp256k1_elgamal();
+        let private_key = buint(0xdecaf);
+        let public_key = elgamal.public_key(&private_key).unwrap();
+
+        // A tally: each vote is an encryption of 0 or 1, and only the sum gets decrypted
+        let votes = [1u32, 0, 1, 1, 0, 1, 1];
+        let tally = votes
+            .iter()
+            .map(|&vote| {
+                elgamal
+                    .encrypt_exponential(&mut rng, &public_key, &buint(vote))
+                    .unwrap()
+            })
+            .reduce(|acc, c| acc + c)
+            .unwrap();
+        let result = elgamal.decrypt_exponential(&private_key, &tally, &buint(votes.len()));
+        assert_eq!(result.unwrap(), buint(5));
+
+        // Sums beyond the bound can't be decoded
+        let big = elgamal
+            .encrypt_exponential(&mut rng, &public_key, &buint(1000))
+            .unwrap();
+        assert_eq!(
+            elgamal
+                .decrypt_exponential(&private_key, &big, &buint(1000))
+                .unwrap(),
+            buint(1000)
+        );
+        assert!(matches!(
+            elgamal.decrypt_exponential(&private_key, &big, &buint(999)),
+            Err(ECCError::NoDiscreteLog)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let mut rng = ChaCha20Rng::seed_from_u64(3);
+        let elgamal = secp256k1_elgamal();
+        let public_key = elgamal.public_key(&buint(5)).unwrap();
+        assert!(matches!(
+            elgamal.public_key(&buint(0)),
+            Err(ECCError::InvalidScalar)
+        ));
+
+        // Encrypting to a point off the curve, or to O
+        let curve = Curve::named("secp256k1").unwrap();
+        let message = buint(42);
+        assert!(matches!(
+            elgamal.encrypt(&mut rng, &curve.point(1, 1), &message),
+            Err(ECCError::PointNotOnCurve)
+        ));
+        assert!(matches!(
+            elgamal.encrypt(&mut rng, &curve.point_at_infinity(), &message),
+            Err(ECCError::PointAtInfinity)
+        ));
+
+        let mut ciphertext = elgamal.encrypt(&mut rng, &public_key, &message).unwrap();
+        ciphertext.c1 = curve.point(1, 1);
+        assert!(matches!(
+            elgamal.decrypt(&buint(5), &ciphertext),
+            Err(ECCError::PointNotOnCurve)
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+//! SEC1 point encoding (SEC 1 v2, sections 2.3.3 and 2.3.4), with coordinates as big-endian
+//! integers of ceil(log2(p) / 8) bytes:
+//!   - the point at infinity is 0x00
+//!   - uncompressed points are 0x04 || x || y
+//!   - compressed points are 0x02 || x when y is even, 0x03 || x when y is odd. Decoding recovers y
+//!     as a square root of x^3 + a2.x^2 + a4.x + a6, picking the one with the right parity.
+
+use num_bigint::BigUint;
+
+use crate::util::bint;
+use crate::{Curve, ECCError, Point};
+
+impl Point {
+    pub fn to_bytes(&self, compressed: bool) -> Vec<u8> {
+        if self.is_at_infinity() {
+            return vec![0x00];
+        }
+        let length = field_length(&self.curve);
+        let y = self.y.value();
+        if compressed {
+            let mut bytes = vec![if y.bit(0) { 0x03 } else { 0x02 }];
+            bytes.extend(to_fixed_bytes(&self.x.value(), length));
+            bytes
+        } else {
+            let mut bytes = vec![0x04];
+            bytes.extend(to_fixed_bytes(&self.x.value(), length));
+            bytes.extend(to_fixed_bytes(&y, length));
+            bytes
+        }
+    }
+
+    /// Decodes a SEC1 encoded point, and checks it's on the curve
+    pub fn from_bytes(curve: &Curve, bytes: &[u8]) -> Result<Point, ECCError> {
+        let length = field_length(curve);
+        let (&prefix, rest) = bytes.split_first().ok_or(ECCError::InvalidEncoding)?;
+        let point = match (prefix, rest.len()) {
+            (0x00, 0) => return Ok(curve.point_at_infinity()),
+            (0x02 | 0x03, l) if l == length => {
+                let x = curve.element(bint(from_field_bytes(curve, rest)?));
+                let y = curve.rhs(&x).sqrt().ok_or(ECCError::PointNotOnCurve)?;
+                let y = if y.value().bit(0) == (prefix == 0x03) {
+                    y
+                } else {
+                    -y
+                };
+                curve.point(bint(x.value()), bint(y.value()))
+            }
+            (0x04, l) if l == 2 * length => {
+                let x = from_field_bytes(curve, &rest[..length])?;
+                let y = from_field_bytes(curve, &rest[length..])?;
+                curve.point(bint(x), bint(y))
+            }
+            _ => return Err(ECCError::InvalidEncoding),
+        };
+        if !curve.contains(point.clone()) {
+            return Err(ECCError::PointNotOnCurve);
+        }
+        Ok(point)
+    }
+}
+
+fn field_length(curve: &Curve) -> usize {
+    curve.p.bits().div_ceil(8) as usize
+}
+
+fn to_fixed_bytes(value: &BigUint, length: usize) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    let mut out = vec![0u8; length - bytes.len()];
+    out.extend(bytes);
+    out
+}
+
+// Coordinates must be reduced: x and x + p would otherwise encode the same point
+fn from_field_bytes(curve: &Curve, bytes: &[u8]) -> Result<BigUint, ECCError> {
+    let value = BigUint::from_bytes_be(bytes);
+    if value >= curve.p {
+        return Err(ECCError::InvalidEncoding);
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+
+    use crate::util::{bint, buint};
+    use crate::{Curve, ECCError, Point};
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    fn curve_p224() -> Curve {
+        let p = bint(2).pow(224) - bint(2).pow(96) + bint(1);
+        let b = BigUint::parse_bytes(
+            b"b4050a850c04b3abf54132565044b0b7d7bfd8ba270b39432355ffb4",
+            16,
+        )
+        .unwrap();
+        Curve::new("P-224", bint(0), bint(-3), bint(b), p)
+    }
+
+    #[test]
+    fn test_secp256k1_vectors() {
+        let curve = Curve::named("secp256k1").unwrap();
+        let g = Point::from_bytes(
+            &curve,
+            &hex("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+        )
+        .unwrap();
+
+        // (k, compressed [k]G, uncompressed [k]G)
+        let vectors = [
+            (1, "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798", "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"),
+            (2, "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5", "04c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee51ae168fea63dc339a3c58419466ceaeef7f632653266d0e1236431a950cfe52a"),
+            (3, "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9", "04f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9388f7b0f632de8140fe337e62a37f3566500a99934c2231b6cb9fd7584b8e672"),
+            (0xc0ffee, "032a5bbcb0eede528e6abe5f2ec50ad7887eb5677af383a460b05ee23bf892dfe5", "042a5bbcb0eede528e6abe5f2ec50ad7887eb5677af383a460b05ee23bf892dfe552c93747550eda8404c8b473786c00dfd8fd1ef4bc033f359ccf5b77bd656d21"),
+        ];
+        for (k, compressed, uncompressed) in vectors {
+            let point = g.mul(buint(k));
+            assert_eq!(to_hex(&point.to_bytes(true)), compressed);
+            assert_eq!(to_hex(&point.to_bytes(false)), uncompressed);
+            assert_eq!(Point::from_bytes(&curve, &hex(compressed)).unwrap(), point);
+            assert_eq!(
+                Point::from_bytes(&curve, &hex(uncompressed)).unwrap(),
+                point
+            );
+        }
+    }
+
+    #[test]
+    fn test_p224_vectors() {
+        // p = 1 (mod 4): decompression goes through Tonelli-Shanks
+        let curve = curve_p224();
+        let g = Point::from_bytes(&curve, &hex("04b70e0cbd6bb4bf7f321390b94a03c1d356c21122343280d6115c1d21bd376388b5f723fb4c22dfe6cd4375a05a07476444d5819985007e34")).unwrap();
+        assert_eq!(
+            to_hex(&g.to_bytes(true)),
+            "02b70e0cbd6bb4bf7f321390b94a03c1d356c21122343280d6115c1d21"
+        );
+
+        let point = Point::from_bytes(
+            &curve,
+            &hex("039217c463bf63a827a9b7633af16868d1837a7e3dfa363c89d988c450"),
+        )
+        .unwrap();
+        assert_eq!(point, g.mul(buint(0xc0ffee)));
+        assert_eq!(to_hex(&point.to_bytes(false)), "049217c463bf63a827a9b7633af16868d1837a7e3dfa363c89d988c4504d477aa3b9d6fc9e583b8365210e13aa6930c73d76bc7ba86e48ca57");
+    }
+
+    #[test]
+    fn test_round_trip_toy_curve() {
+        // Field elements on 2 bytes, p = 1 (mod 4)
+        let curve = Curve::new("c", 0, 1, 3, 10009);
+        for point in curve.points().take(50) {
+            for compressed in [true, false] {
+                let bytes = point.to_bytes(compressed);
+                assert_eq!(Point::from_bytes(&curve, &bytes).unwrap(), point);
+            }
+        }
+        assert_eq!(curve.point_at_infinity().to_bytes(true), vec![0x00]);
+    }
+
+    #[test]
+    fn test_invalid_encodings() {
+        let curve = Curve::named("secp256k1").unwrap();
+        let g = hex("0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8");
+
+        // Off-curve: last bit of y flipped
+        let mut off_curve = g.clone();
+        off_curve[64] ^= 1;
+        assert!(matches!(
+            Point::from_bytes(&curve, &off_curve),
+            Err(ECCError::PointNotOnCurve)
+        ));
+        // x = 5 gives x^3 + 7 = 132, which isn't a square mod p
+        let mut no_y = vec![0x02];
+        no_y.extend([0; 32]);
+        no_y[32] = 5;
+        assert!(matches!(
+            Point::from_bytes(&curve, &no_y),
+            Err(ECCError::PointNotOnCurve)
+        ));
+        // Wrong lengths and prefixes
+        for bytes in [
+            &[][..],
+            &g[..64],
+            &g[..33],
+            &[0x05; 65][..],
+            &[0x00, 0x00][..],
+        ] {
+            assert!(matches!(
+                Point::from_bytes(&curve, bytes),
+                Err(ECCError::InvalidEncoding)
+            ));
+        }
+        // x >= p
+        let mut too_big = vec![0x02];
+        too_big.extend([0xff; 32]);
+        assert!(matches!(
+            Point::from_bytes(&curve, &too_big),
+            Err(ECCError::InvalidEncoding)
+        ));
+    }
+}
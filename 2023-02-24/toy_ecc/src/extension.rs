@@ -0,0 +1,661 @@
+//! Extension fields F_p^k = F_p[t] / (m(t)), for a monic irreducible polynomial m of degree k.
+//!
+//! Elements are polynomials in t of degree < k, with coefficients in F_p (see `Polynomial`):
+//!   - multiplication reduces the product modulo m
+//!   - inversion runs the extended Euclidean algorithm on polynomials: u.a + v.m = 1 gives a^-1 = u
+//!   - the Frobenius map a -> a^p is a field automorphism, which fixes exactly F_p
+//!
+//! Curves over F_p^k are `Curve<ExtElement>`. `Curve::lift` gives the same curve over an extension
+//! field: it has many more points, among which the n-torsion points missing from E(F_p) when k is
+//! the embedding degree of n (see `embedding_degree`). This is where pairings live.
+
+use std::fmt;
+use std::ops;
+use std::sync::Arc;
+
+use num_bigint::{BigUint, ToBigInt, ToBigUint};
+
+use crate::element::{Element, Field, FieldElement};
+use crate::polynomial::Polynomial;
+use crate::util::buint;
+use crate::{Curve, ECCError, Point};
+
+/// F_p[t] / (m(t))
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExtensionField {
+    base: Arc<Field>,
+    // Monic, of degree k
+    modulus: Polynomial,
+}
+
+impl ExtensionField {
+    /// F_p[t] / (m(t)), with the coefficients of m lowest degree first: [1, 0, 1] is t^2 + 1.
+    /// m is made monic, and must be irreducible over F_p.
+    pub fn new<BUINT: ToBigUint, BINT: ToBigInt>(
+        p: BUINT,
+        modulus: &[BINT],
+    ) -> Result<Arc<Self>, ECCError> {
+        let base = Field::new(p);
+        let modulus = Polynomial::new(modulus, &base).monic();
+        if !modulus.is_irreducible() {
+            return Err(ECCError::NotIrreducible);
+        }
+        Ok(Arc::new(Self { base, modulus }))
+    }
+
+    /// k, for F_p^k
+    pub fn degree(&self) -> usize {
+        self.modulus.degree().unwrap()
+    }
+
+    pub fn characteristic(&self) -> &BigUint {
+        self.base.modulus()
+    }
+
+    /// Number of elements: p^k
+    pub fn order(&self) -> BigUint {
+        self.characteristic().pow(self.degree() as u32)
+    }
+
+    /// F_p
+    pub fn base(&self) -> &Arc<Field> {
+        &self.base
+    }
+
+    /// m(t)
+    pub fn modulus(&self) -> &Polynomial {
+        &self.modulus
+    }
+}
+
+impl fmt::Display for ExtensionField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "F_{}[t]/({})",
+            self.characteristic(),
+            self.modulus.format_with("t")
+        )
+    }
+}
+
+/// An element of F_p^k: a polynomial in t of degree < k
+#[derive(Clone, PartialEq, Eq)]
+pub struct ExtElement {
+    // Exactly k coefficients, lowest degree first
+    coefficients: Vec<Element>,
+    field: Arc<ExtensionField>,
+}
+
+impl ExtElement {
+    /// Creates an element from its coefficients, lowest degree first: [3, 1] is t + 3.
+    /// Coefficients are reduced mod p, and polynomials of degree k or more are reduced mod m.
+    pub fn new<T: ToBigInt>(coefficients: &[T], field: &Arc<ExtensionField>) -> Self {
+        Self::from_polynomial(Polynomial::new(coefficients, &field.base), field)
+    }
+
+    /// Same as `new`, with coefficients which are already elements of F_p
+    pub fn from_elements(
+        coefficients: Vec<Element>,
+        field: &Arc<ExtensionField>,
+    ) -> Result<Self, ECCError> {
+        let polynomial = Polynomial::from_elements(coefficients, &field.base)?;
+        Ok(Self::from_polynomial(polynomial, field))
+    }
+
+    /// Reduces mod m: the polynomial must have its coefficients in F_p
+    pub fn from_polynomial(polynomial: Polynomial, field: &Arc<ExtensionField>) -> Self {
+        let (_, remainder) = polynomial.divmod(&field.modulus).unwrap();
+        let mut coefficients = remainder.coefficients().to_vec();
+        coefficients.resize(field.degree(), Element::in_field(0, &field.base));
+        Self {
+            coefficients,
+            field: field.clone(),
+        }
+    }
+
+    /// Coefficients, lowest degree first
+    pub fn coefficients(&self) -> &[Element] {
+        &self.coefficients
+    }
+
+    /// The polynomial in t of degree < k this element is
+    pub fn to_polynomial(&self) -> Polynomial {
+        Polynomial::from_elements(self.coefficients.clone(), &self.field.base).unwrap()
+    }
+
+    pub fn field(&self) -> &Arc<ExtensionField> {
+        &self.field
+    }
+
+    /// The element of F_p self is equal to, if any
+    pub fn to_base(&self) -> Option<Element> {
+        if self.coefficients[1..].iter().all(|c| c.is_zero()) {
+            Some(self.coefficients[0].clone())
+        } else {
+            None
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.iter().all(|c| c.is_zero())
+    }
+
+    pub fn pow(&self, exponent: usize) -> Self {
+        self.pow_big(&buint(exponent))
+    }
+
+    pub fn pow_big(&self, exponent: &BigUint) -> Self {
+        let power = self
+            .to_polynomial()
+            .pow_mod(exponent, &self.field.modulus)
+            .unwrap();
+        Self::from_polynomial(power, &self.field)
+    }
+
+    /// self^p. Iterating it k times gives back self.
+    pub fn frobenius(&self) -> Self {
+        self.pow_big(self.field.characteristic())
+    }
+
+    /// Multiplicative inverse, through the extended Euclidean algorithm on polynomials
+    pub fn inverse(&self) -> Result<Self, ECCError> {
+        if self.is_zero() {
+            return Err(ECCError::NotInvertible);
+        }
+        // Invariant: s.self = r (mod m)
+        let base = &self.field.base;
+        let (mut r0, mut r1) = (self.field.modulus.clone(), self.to_polynomial());
+        let (mut s0, mut s1) = (Polynomial::zero(base), Polynomial::new(&[1], base));
+        while !r1.is_zero() {
+            let (q, r) = r0.divmod(&r1)?;
+            let s = s0 - q * s1.clone();
+            (r0, r1) = (r1, r);
+            (s0, s1) = (s1, s);
+        }
+        // m is irreducible: the GCD r0 is a non-zero constant
+        let gcd_inverse = Polynomial::constant(r0.coefficients()[0].inverse()?);
+        Ok(Self::from_polynomial(s0 * gcd_inverse, &self.field))
+    }
+
+    /// Same as `+`, but returns an error instead of panicking when the fields differ
+    pub fn checked_add(&self, other: &Self) -> Result<Self, ECCError> {
+        self.check_field(other)?;
+        let coefficients = self
+            .coefficients
+            .iter()
+            .zip(&other.coefficients)
+            .map(|(a, b)| a.clone() + b.clone())
+            .collect();
+        Ok(self.with_coefficients(coefficients))
+    }
+
+    /// Same as `-`, but returns an error instead of panicking when the fields differ
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, ECCError> {
+        self.check_field(other)?;
+        let coefficients = self
+            .coefficients
+            .iter()
+            .zip(&other.coefficients)
+            .map(|(a, b)| a.clone() - b.clone())
+            .collect();
+        Ok(self.with_coefficients(coefficients))
+    }
+
+    /// Same as `*`, but returns an error instead of panicking when the fields differ
+    pub fn checked_mul(&self, other: &Self) -> Result<Self, ECCError> {
+        self.check_field(other)?;
+        Ok(Self::from_polynomial(
+            self.to_polynomial() * other.to_polynomial(),
+            &self.field,
+        ))
+    }
+
+    /// Same as `/`, but returns an error instead of panicking when the fields differ,
+    /// or when `other` is zero
+    pub fn checked_div(&self, other: &Self) -> Result<Self, ECCError> {
+        self.check_field(other)?;
+        self.checked_mul(&other.inverse()?)
+    }
+
+    // Element of the same field, from exactly k coefficients
+    fn with_coefficients(&self, coefficients: Vec<Element>) -> Self {
+        Self {
+            coefficients,
+            field: self.field.clone(),
+        }
+    }
+
+    fn check_field(&self, other: &Self) -> Result<(), ECCError> {
+        if !Arc::ptr_eq(&self.field, &other.field) && self.field != other.field {
+            return Err(ECCError::ModulusMismatch);
+        }
+        Ok(())
+    }
+}
+
+impl FieldElement for ExtElement {
+    fn embed<T: ToBigInt>(&self, value: T) -> Self {
+        Self::new(&[value], &self.field)
+    }
+
+    fn characteristic(&self) -> &BigUint {
+        self.field.characteristic()
+    }
+
+    fn field_order(&self) -> BigUint {
+        self.field.order()
+    }
+
+    fn is_zero(&self) -> bool {
+        ExtElement::is_zero(self)
+    }
+
+    fn pow(&self, exponent: usize) -> Self {
+        ExtElement::pow(self, exponent)
+    }
+
+    fn pow_big(&self, exponent: &BigUint) -> Self {
+        ExtElement::pow_big(self, exponent)
+    }
+
+    fn checked_add(&self, other: &Self) -> Result<Self, ECCError> {
+        ExtElement::checked_add(self, other)
+    }
+
+    fn checked_sub(&self, other: &Self) -> Result<Self, ECCError> {
+        ExtElement::checked_sub(self, other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Result<Self, ECCError> {
+        ExtElement::checked_mul(self, other)
+    }
+
+    fn checked_div(&self, other: &Self) -> Result<Self, ECCError> {
+        ExtElement::checked_div(self, other)
+    }
+}
+
+impl fmt::Display for ExtElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (mod {}, {})",
+            self.to_polynomial().format_with("t"),
+            self.field.characteristic(),
+            self.field.modulus.format_with("t")
+        )
+    }
+}
+
+impl fmt::Debug for ExtElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtElement")
+            .field("value", &self.to_polynomial().format_with("t"))
+            .field("field", &self.field.to_string())
+            .finish()
+    }
+}
+
+impl ops::Neg for ExtElement {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        let coefficients = self.coefficients.iter().map(|c| -c.clone()).collect();
+        self.with_coefficients(coefficients)
+    }
+}
+
+impl ops::Add for ExtElement {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.checked_add(&other).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl ops::Sub for ExtElement {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.checked_sub(&other).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl ops::Mul for ExtElement {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        self.checked_mul(&other).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl ops::Div for ExtElement {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        self.checked_div(&other).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl Curve {
+    /// The same curve, over an extension of its field. E(F_p) is a subgroup of the result.
+    pub fn lift(&self, field: &Arc<ExtensionField>) -> Result<Curve<ExtElement>, ECCError> {
+        let lift = |c: &Element| ExtElement::from_elements(vec![c.clone()], field);
+        Curve::from_elements(
+            &self.name,
+            lift(&self.a2)?,
+            lift(&self.a4)?,
+            lift(&self.a6)?,
+        )
+    }
+}
+
+impl Point {
+    /// The same point, on `curve`: this point's curve lifted to an extension field
+    pub fn lift(&self, curve: &Curve<ExtElement>) -> Result<Point<ExtElement>, ECCError> {
+        let field = curve.a2.field();
+        if self.curve.lift(field)? != *curve {
+            return Err(ECCError::CurveMismatch);
+        }
+        if self.is_at_infinity() {
+            return Ok(curve.point_at_infinity());
+        }
+        curve.point_from_elements(
+            ExtElement::from_elements(vec![self.x.clone()], field)?,
+            ExtElement::from_elements(vec![self.y.clone()], field)?,
+        )
+    }
+}
+
+impl fmt::Display for Curve<ExtElement> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Curve {}: y^2 = x^3", self.name)?;
+        for (coefficient, monomial) in [(&self.a2, "x^2"), (&self.a4, "x"), (&self.a6, "")] {
+            if !coefficient.is_zero() {
+                write!(
+                    f,
+                    " + ({}){}",
+                    coefficient.to_polynomial().format_with("t"),
+                    monomial
+                )?;
+            }
+        }
+        write!(f, " over {}>", self.a2.field)
+    }
+}
+
+impl fmt::Display for Point<ExtElement> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_at_infinity() {
+            write!(f, "<Point at Infinity>")
+        } else {
+            write!(
+                f,
+                "<Point on curve {}: x={}, y={}>",
+                self.curve.name,
+                self.x.to_polynomial().format_with("t"),
+                self.y.to_polynomial().format_with("t")
+            )
+        }
+    }
+}
+
+/// Embedding degree of a subgroup of order n, in a curve over F_q: the smallest k such that n
+/// divides q^k - 1, i.e. such that F_q^k contains the n-th roots of unity. None if k > max_k.
+pub fn embedding_degree(q: &BigUint, n: &BigUint, max_k: usize) -> Option<usize> {
+    let one = buint(1) % n;
+    let q = q % n;
+    let mut power = q.clone();
+    for k in 1..=max_k {
+        if power == one {
+            return Some(k);
+        }
+        power = power * &q % n;
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use num_bigint::BigUint;
+
+    use crate::extension::{embedding_degree, ExtElement, ExtensionField};
+    use crate::util::buint;
+    use crate::{Curve, ECCError};
+
+    // F_59^2 = F_59[i], with i^2 = -1: 59 = 3 (mod 4), so -1 isn't a square mod 59
+    fn f59_squared() -> Arc<ExtensionField> {
+        ExtensionField::new(59, &[1, 0, 1]).unwrap()
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let f = f59_squared();
+        let i = ExtElement::new(&[0, 1], &f);
+        assert_eq!(i.pow(2), ExtElement::new(&[-1], &f));
+        assert_eq!(i.pow(4), ExtElement::new(&[1], &f));
+
+        // (2 + 3i)(4 + 5i) = 8 - 15 + 22i
+        let a = ExtElement::new(&[2, 3], &f);
+        let b = ExtElement::new(&[4, 5], &f);
+        assert_eq!(a.clone() * b.clone(), ExtElement::new(&[-7, 22], &f));
+        assert_eq!(a.clone() + b.clone(), ExtElement::new(&[6, 8], &f));
+        assert_eq!(a.clone() - b, ExtElement::new(&[-2, -2], &f));
+        assert_eq!(-a.clone() + a, ExtElement::new(&[0], &f));
+
+        // Polynomials of degree >= 2 are reduced: t^3 + t = 0
+        assert!(ExtElement::new(&[0, 1, 0, 1], &f).is_zero());
+        assert_eq!(
+            format!("{}", ExtElement::new(&[5, 1], &f)),
+            "t + 5 (mod 59, t^2 + 1)"
+        );
+        assert_eq!(f.order(), buint(3481u32));
+    }
+
+    #[test]
+    fn test_inverse() {
+        let f = ExtensionField::new(7, &[-2, 0, 0, 1]).unwrap();
+        let one = ExtElement::new(&[1], &f);
+        for c0 in 0..7 {
+            for c1 in 0..7 {
+                for c2 in [0, 3] {
+                    let a = ExtElement::new(&[c0, c1, c2], &f);
+                    if a.is_zero() {
+                        assert!(matches!(a.inverse(), Err(ECCError::NotInvertible)));
+                    } else {
+                        assert_eq!(a.clone() * a.inverse().unwrap(), one);
+                        assert_eq!(one.clone() / a.clone() * a, one);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_irreducibility() {
+        // -1 is a square mod 13: t^2 + 1 = (t - 5)(t + 5)
+        assert!(matches!(
+            ExtensionField::new(13, &[1, 0, 1]),
+            Err(ECCError::NotIrreducible)
+        ));
+        // No roots, but t^4 + 1 = (t^2 + t + 2)(t^2 + 2t + 2) over F_3
+        assert!(matches!(
+            ExtensionField::new(3, &[1, 0, 0, 0, 1]),
+            Err(ECCError::NotIrreducible)
+        ));
+        // Constants don't define extensions
+        assert!(matches!(
+            ExtensionField::new(7, &[3, 0]),
+            Err(ECCError::NotIrreducible)
+        ));
+        // 2 isn't a cube mod 7. The modulus is made monic.
+        let f = ExtensionField::new(7, &[-4, 0, 0, 2]).unwrap();
+        assert_eq!(f.degree(), 3);
+        assert_eq!(f.to_string(), "F_7[t]/(t^3 + 5)");
+        // F_16 = F_2[t]/(t^4 + t + 1)
+        assert_eq!(
+            ExtensionField::new(2, &[1, 1, 0, 0, 1]).unwrap().order(),
+            buint(16)
+        );
+    }
+
+    #[test]
+    fn test_frobenius() {
+        let f = ExtensionField::new(7, &[-2, 0, 0, 1]).unwrap();
+        let a = ExtElement::new(&[3, 1, 4], &f);
+        let b = ExtElement::new(&[1, 5, 2], &f);
+        let order = f.order();
+
+        // Automorphism of order k = 3, fixing exactly F_7
+        assert_eq!(a.frobenius().frobenius().frobenius(), a);
+        assert_ne!(a.frobenius(), a);
+        assert_eq!(
+            ExtElement::new(&[5], &f).frobenius(),
+            ExtElement::new(&[5], &f)
+        );
+        assert_eq!(
+            (a.clone() * b.clone()).frobenius(),
+            a.frobenius() * b.frobenius()
+        );
+        assert_eq!(
+            (a.clone() + b.clone()).frobenius(),
+            a.frobenius() + b.frobenius()
+        );
+        // Fermat: a^(p^k - 1) = 1
+        assert_eq!(
+            a.pow_big(&(order - BigUint::from(1u32))),
+            ExtElement::new(&[1], &f)
+        );
+        // Over F_59^2, Frobenius is complex conjugation
+        let f = f59_squared();
+        assert_eq!(
+            ExtElement::new(&[2, 3], &f).frobenius(),
+            ExtElement::new(&[2, -3], &f)
+        );
+    }
+
+    #[test]
+    fn test_group_law_over_extension() {
+        // y^2 = x^3 + 3 has 13 points over F_7, so 7^2 + 1 - (1^2 - 2.7) = 39 points over F_49
+        let curve = Curve::new("c", 0, 0, 3, 7);
+        assert_eq!(curve.order().unwrap(), buint(13));
+        let f = ExtensionField::new(7, &[1, 0, 1]).unwrap();
+        let lifted = curve.lift(&f).unwrap();
+
+        let mut points = vec![lifted.point_at_infinity()];
+        for x0 in 0..7 {
+            for x1 in 0..7 {
+                let x = ExtElement::new(&[x0, x1], &f);
+                for y0 in 0..7 {
+                    for y1 in 0..7 {
+                        let y = ExtElement::new(&[y0, y1], &f);
+                        let point = lifted.point_from_elements(x.clone(), y).unwrap();
+                        if !point.is_at_infinity() && lifted.contains(point.clone()) {
+                            points.push(point);
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(points.len(), 39);
+
+        for a in &points {
+            assert!((a.clone() + -a.clone()).is_at_infinity());
+            assert!(a.mul(buint(39)).is_at_infinity());
+            assert_eq!(a.mul(buint(5)), a.mul_affine(buint(5)));
+            for b in &points {
+                let sum = a.clone() + b.clone();
+                assert!(lifted.contains(sum.clone()));
+                assert_eq!(sum, b.clone() + a.clone());
+            }
+        }
+        for a in &points[..8] {
+            for b in &points[..8] {
+                for c in &points[..8] {
+                    assert_eq!(
+                        (a.clone() + b.clone()) + c.clone(),
+                        a.clone() + (b.clone() + c.clone())
+                    );
+                }
+            }
+        }
+
+        // E(F_7) is a subgroup
+        let p = curve.points().nth(1).unwrap();
+        let q = p.lift(&lifted).unwrap();
+        assert_eq!(p.mul(buint(4)).lift(&lifted).unwrap(), q.mul(buint(4)));
+        let other = Curve::new("other", 0, 0, 5, 7).lift(&f).unwrap();
+        assert!(matches!(p.lift(&other), Err(ECCError::CurveMismatch)));
+    }
+
+    #[test]
+    fn test_supersingular_curve() {
+        // y^2 = x^3 + x is supersingular over F_p when p = 3 (mod 4): it has p + 1 = 60 points over
+        // F_59, and its subgroup of order 5 has embedding degree 2
+        let curve = Curve::new("c", 0, 1, 0, 59);
+        assert_eq!(curve.order().unwrap(), buint(60));
+        assert!(curve.is_supersingular());
+        assert!(!Curve::new("c", 0, 3, 8, 13).is_supersingular());
+        assert_eq!(embedding_degree(&buint(59), &buint(5), 10), Some(2));
+        assert_eq!(embedding_degree(&buint(59), &buint(7), 10), Some(6));
+        assert_eq!(embedding_degree(&buint(13), &buint(9), 2), None);
+
+        // Over F_59^2 there are (p + 1)^2 points: count them with Euler's criterion
+        let f = f59_squared();
+        let lifted = curve.lift(&f).unwrap();
+        let half_order = (f.order() - buint(1)) >> 1;
+        let one = ExtElement::new(&[1], &f);
+        let mut count = 1;
+        for x0 in 0..59 {
+            for x1 in 0..59 {
+                let rhs = lifted.rhs(&ExtElement::new(&[x0, x1], &f));
+                count += if rhs.is_zero() {
+                    1
+                } else if rhs.pow_big(&half_order) == one {
+                    2
+                } else {
+                    0
+                };
+            }
+        }
+        assert_eq!(count, 3600);
+    }
+
+    #[test]
+    fn test_distortion_map() {
+        // On y^2 = x^3 + x, (x, y) -> (-x, iy) maps E(F_59)[5] to the rest of E(F_59^2)[5]
+        let curve = Curve::new("c", 0, 1, 0, 59);
+        let f = f59_squared();
+        let lifted = curve.lift(&f).unwrap();
+        let i = ExtElement::new(&[0, 1], &f);
+
+        let p = curve
+            .points()
+            .map(|point| point.mul(buint(12)))
+            .find(|point| !point.is_at_infinity())
+            .unwrap();
+        let lifted_p = p.lift(&lifted).unwrap();
+        let q = lifted
+            .point_from_elements(-lifted_p.x.clone(), i * lifted_p.y.clone())
+            .unwrap();
+
+        assert!(lifted.contains(q.clone()));
+        assert!(q.y.to_base().is_none());
+        assert!(q.mul(buint(5)).is_at_infinity());
+        for j in 0..5u32 {
+            assert_ne!(lifted_p.mul(buint(j)), q);
+        }
+        // i^p = -i: Frobenius maps Q to -Q
+        let frobenius_q = lifted
+            .point_from_elements(q.x.frobenius(), q.y.frobenius())
+            .unwrap();
+        assert_eq!(frobenius_q, -q.clone());
+        assert!(q.to_string().starts_with("<Point on curve c: x="));
+        assert_eq!(
+            lifted.to_string(),
+            "<Curve c: y^2 = x^3 + (1)x over F_59[t]/(t^2 + 1)>"
+        );
+    }
+}
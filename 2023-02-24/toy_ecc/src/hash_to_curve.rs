@@ -0,0 +1,688 @@
+//! Hashing arbitrary bytes to a point, following RFC 9380 (https://www.rfc-editor.org/rfc/rfc9380).
+//!
+//! Everything starts with `expand_message_xmd`, which stretches a message into as many
+//! pseudo-random bytes as needed, and `hash_to_field`, which turns them into field elements.
+//! Then, two ways to get a point:
+//!   - try-and-increment: hash (message, counter) to x until x^3 + a4.x + a6 is a square. Simple,
+//!     but the number of tries (and the running time) depends on the message.
+//!   - the Simplified SWU map: a formula that sends any field element u to a point, with the same
+//!     operations for every u. It needs a4.a6 != 0. Otherwise, we map to a 3-isogenous curve which
+//!     satisfies it, and then back to ours (see `Isogeny`).
+//!
+//! As in the `ladder` module, "the same operations" is about the algorithm: `BigUint` arithmetic
+//! underneath still takes variable time.
+//!
+//! `Sswu::hash_to_curve` adds the images of two field elements, so that its output is
+//! indistinguishable from a random point: this is the random-oracle construction of the RFC.
+
+use std::sync::Arc;
+
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::element::{Element, Field};
+use crate::polynomial::Polynomial;
+use crate::util::{bint, buint};
+use crate::{Curve, ECCError, Point};
+
+/// RFC 9380, section 5.3.1: expands `msg` into `len_in_bytes` pseudo-random bytes with SHA-256,
+/// with a domain separation tag (DST). Long tags are hashed first (section 5.3.3).
+pub fn expand_message_xmd(
+    msg: &[u8],
+    dst: &[u8],
+    len_in_bytes: usize,
+) -> Result<Vec<u8>, ECCError> {
+    let ell = len_in_bytes.div_ceil(32);
+    if ell > 255 || len_in_bytes > 65535 {
+        return Err(ECCError::InvalidExpandLength);
+    }
+    let dst = if dst.len() > 255 {
+        Sha256::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(dst)
+            .finalize()
+            .to_vec()
+    } else {
+        dst.to_vec()
+    };
+    let dst_prime = [dst.as_slice(), &[dst.len() as u8]].concat();
+
+    // b_0 = H(Z_pad || msg || l_i_b_str || 0 || DST_prime)
+    let b_0 = Sha256::new()
+        .chain_update([0u8; 64])
+        .chain_update(msg)
+        .chain_update((len_in_bytes as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    // b_i = H((b_0 xor b_(i-1)) || i || DST_prime), with b_1 = H(b_0 || 1 || DST_prime)
+    let mut uniform_bytes = Vec::with_capacity(ell * 32);
+    let mut b_i = [0u8; 32];
+    for i in 1..=ell {
+        let mut input = b_0;
+        for (byte, previous) in input.iter_mut().zip(b_i) {
+            *byte ^= previous;
+        }
+        b_i = Sha256::new()
+            .chain_update(input)
+            .chain_update([i as u8])
+            .chain_update(&dst_prime)
+            .finalize()
+            .into();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+    Ok(uniform_bytes)
+}
+
+/// RFC 9380, section 5.2: `count` elements of F_p, each from ceil((log2(p) + 128) / 8) expanded
+/// bytes. The extra 128 bits make the bias of the reduction mod p negligible.
+pub fn hash_to_field(
+    msg: &[u8],
+    dst: &[u8],
+    count: usize,
+    field: &Arc<Field>,
+) -> Result<Vec<Element>, ECCError> {
+    let length = (field.modulus().bits() as usize + 128).div_ceil(8);
+    let uniform_bytes = expand_message_xmd(msg, dst, count * length)?;
+    Ok(uniform_bytes
+        .chunks(length)
+        .map(|chunk| Element::in_field(bint(BigUint::from_bytes_be(chunk)), field))
+        .collect())
+}
+
+/// Hashes (msg, counter) to x for counter = 0, 1, 2... until x is on the curve, then takes the
+/// even y. Only meant for teaching: the running time leaks how many counters were tried.
+pub fn try_and_increment(curve: &Curve, msg: &[u8], dst: &[u8]) -> Result<Point, ECCError> {
+    for counter in 0..=u8::MAX {
+        let input = [msg, &[counter]].concat();
+        let x = hash_to_field(&input, dst, 1, curve.a2.field())?.remove(0);
+        if let Some(y) = curve.rhs(&x).sqrt() {
+            let y = if y.value().bit(0) { -y } else { y };
+            let point = curve.point_from_elements(x, y)?;
+            return Ok(point.mul(cofactor(curve)));
+        }
+    }
+    Err(ECCError::EncodingFailed)
+}
+
+/// A 3-isogeny from Vélu's formulas, defined by the x-coordinate x0 of its kernel {O, (x0, ±y0)}:
+///     x -> x + v/(x - x0) + u/(x - x0)^2
+///     y -> y.(1 - v/(x - x0)^2 - 2u/(x - x0)^3)
+/// with v = 2(3.x0^2 + a4) and u = 4.y0^2. The image curve is y^2 = x^3 + (a4 - 5v)x + a6 - 7(u + x0.v).
+/// y0 itself doesn't need to be in F_p: only y0^2 = x0^3 + a4.x0 + a6 is used.
+#[derive(Debug, Clone)]
+pub struct Isogeny {
+    domain: Curve,
+    codomain: Curve,
+    kernel_x: Element,
+    v: Element,
+    u: Element,
+}
+
+impl Isogeny {
+    /// Errors with `NotTorsionPoint` unless x0 is a root of the 3-division polynomial
+    /// 3x^4 + 6.a4.x^2 + 12.a6.x - a4^2.
+    pub fn velu(curve: &Curve, kernel_x: &Element) -> Result<Self, ECCError> {
+        if !curve.a2.is_zero() {
+            return Err(ECCError::NotShortWeierstrass);
+        }
+        if !division_polynomial_3(curve)?.contains(kernel_x) {
+            return Err(ECCError::NotTorsionPoint);
+        }
+        let x0 = kernel_x.clone();
+        let y0_squared = curve.rhs(&x0);
+        let v = curve.element(2) * (curve.element(3) * x0.pow(2) + curve.a4.clone());
+        let u = curve.element(4) * y0_squared;
+        let a4 = curve.a4.clone() - curve.element(5) * v.clone();
+        let a6 = curve.a6.clone() - curve.element(7) * (u.clone() + x0.clone() * v.clone());
+        let name = format!("{} (3-isogenous)", curve.name());
+        Ok(Self {
+            domain: curve.clone(),
+            codomain: Curve::new(
+                &name,
+                bint(0),
+                bint(a4.value()),
+                bint(a6.value()),
+                curve.p.clone(),
+            ),
+            kernel_x: x0,
+            v,
+            u,
+        })
+    }
+
+    pub fn domain(&self) -> &Curve {
+        &self.domain
+    }
+
+    pub fn codomain(&self) -> &Curve {
+        &self.codomain
+    }
+
+    /// Same operations for every point: the kernel and O are sent to O with conditional moves,
+    /// rather than with an early return.
+    pub fn map(&self, point: &Point) -> Result<Point, ECCError> {
+        if point.curve != self.domain {
+            return Err(ECCError::CurveMismatch);
+        }
+        let c = &self.codomain;
+        let t = inv0(&(point.x.clone() - self.kernel_x.clone()));
+        let (v, u) = (self.v.clone(), self.u.clone());
+        let x = point.x.clone() + v.clone() * t.clone() + u.clone() * t.pow(2);
+        let y = point.y.clone() * (c.element(1) - v * t.pow(2) - c.element(2) * u * t.pow(3));
+
+        let to_infinity = point.is_at_infinity() | t.is_zero();
+        let flag = c.element(u8::from(to_infinity));
+        let x = cmov(&x, &c.zero(), &flag);
+        let y = cmov(&y, &c.zero(), &flag);
+        let mut image = c.point_from_elements(x, y)?;
+        image.infinity = to_infinity;
+        Ok(image)
+    }
+}
+
+/// The Simplified Shallue-van de Woestijne-Ulas map of RFC 9380 (section 6.6.2), for a curve
+/// y^2 = x^3 + a4.x + a6. When a4.a6 = 0 (like secp256k1), the map targets a 3-isogenous curve E'
+/// with a4'.a6' != 0. Mapping back goes through a second 3-isogeny E' -> E'', the dual of the first:
+/// E'' is E with coordinates scaled by 3 (Vélu's isogenies compose into [3]).
+#[derive(Debug, Clone)]
+pub struct Sswu {
+    curve: Curve,
+    // The curve the map itself lands on: E, or E' when E has a4.a6 = 0
+    target: Curve,
+    z: Element,
+    sqrt_ratio: SqrtRatio,
+    // E' -> E'', and s such that (x, y) -> (s^2.x, s^3.y) is an isomorphism E'' -> E
+    isogeny: Option<(Isogeny, Element)>,
+}
+
+impl Sswu {
+    /// When a4.a6 = 0, the 3-isogenous curve E' is the first suitable one we find, not the one
+    /// from the RFC: the points differ from other implementations. See `with_isogenous_curve`.
+    pub fn new(curve: &Curve) -> Result<Self, ECCError> {
+        if !curve.a2.is_zero() {
+            return Err(ECCError::NotShortWeierstrass);
+        }
+        if (curve.a4.clone() * curve.a6.clone()).is_zero() {
+            let target = find_isogenous_curve(curve)?;
+            return Self::with_isogenous_curve(curve, &target);
+        }
+        Self::build(curve, curve.clone(), None)
+    }
+
+    /// Maps to a given 3-isogenous curve E' with a4'.a6' != 0, then back to this curve: RFC 9380
+    /// fixes E' for curves like secp256k1 (appendix E.1), so that all implementations agree.
+    pub fn with_isogenous_curve(curve: &Curve, target: &Curve) -> Result<Self, ECCError> {
+        if !curve.a2.is_zero() || !target.a2.is_zero() {
+            return Err(ECCError::NotShortWeierstrass);
+        }
+        if (target.a4.clone() * target.a6.clone()).is_zero() {
+            return Err(ECCError::NoIsogeny);
+        }
+        let isogeny = find_isogeny(target, curve)?.ok_or(ECCError::NoIsogeny)?;
+        Self::build(curve, target.clone(), Some(isogeny))
+    }
+
+    fn build(
+        curve: &Curve,
+        target: Curve,
+        isogeny: Option<(Isogeny, Element)>,
+    ) -> Result<Self, ECCError> {
+        let z = find_z(&target)?;
+        Ok(Self {
+            sqrt_ratio: SqrtRatio::new(&z),
+            z,
+            curve: curve.clone(),
+            target,
+            isogeny,
+        })
+    }
+
+    /// The non-square Z from RFC 9380, appendix H.2 (Z = -10 for P-256)
+    pub fn z(&self) -> &Element {
+        &self.z
+    }
+
+    /// The curve SSWU maps to before the isogeny, if any: this curve, or a 3-isogenous one.
+    pub fn target(&self) -> &Curve {
+        &self.target
+    }
+
+    /// Sends any field element to a point on the curve, with the same operations whatever u
+    /// (RFC 9380, appendix F.2). Branches are replaced by conditional moves a + c.(b - a), like in
+    /// `Point::mul_ct`, and the square root is `sqrt_ratio`, a fixed sequence of exponentiations.
+    pub fn map_to_curve(&self, u: &Element) -> Result<Point, ECCError> {
+        let c = &self.target;
+        let (a, b, z) = (c.a4.clone(), c.a6.clone(), self.z.clone());
+        let bit = |flag: bool| c.element(u8::from(flag));
+
+        // x1 = -b/a.(1 + 1/(Z^2.u^4 + Z.u^2)), or b/(Z.a) when the denominator is 0, as a fraction
+        // x1 = n/d, so that the only inversion is at the end
+        let z_u2 = z.clone() * u.pow(2);
+        let tv2 = z_u2.pow(2) + z_u2.clone();
+        let n = b.clone() * (tv2.clone() + c.element(1));
+        let d = a.clone() * cmov(&z, &-tv2.clone(), &bit(!tv2.is_zero()));
+        // g(x1) = (n^3 + a.n.d^2 + b.d^3) / d^3
+        let d3 = d.pow(3);
+        let gx1_numerator = n.pow(3) + a * n.clone() * d.pow(2) + b * d3.clone();
+
+        // If g(x1) isn't a square, x2 = Z.u^2.x1 is on the curve, with y2 = Z.u^3.y1 for y1^2 = Z.g(x1)
+        let (gx1_is_square, y1) = self.sqrt_ratio.compute(&gx1_numerator, &d3);
+        let x = cmov(&(z_u2.clone() * n.clone()), &n, &bit(gx1_is_square));
+        let y = cmov(&(z_u2 * u.clone() * y1.clone()), &y1, &bit(gx1_is_square));
+        // y has the sign of u
+        let y = cmov(&-y.clone(), &y, &bit(sgn0(u) == sgn0(&y)));
+        let point = c.point_from_elements(x * inv0(&d), y)?;
+
+        match &self.isogeny {
+            None => Ok(point),
+            // O stays O
+            Some((isogeny, s)) => {
+                let image = isogeny.map(&point)?;
+                let mut scaled = self
+                    .curve
+                    .point_from_elements(s.pow(2) * image.x, s.pow(3) * image.y)?;
+                scaled.infinity = image.infinity;
+                Ok(scaled)
+            }
+        }
+    }
+
+    /// hash_to_curve from RFC 9380: map two field elements, add the points, clear the cofactor.
+    pub fn hash_to_curve(&self, msg: &[u8], dst: &[u8]) -> Result<Point, ECCError> {
+        let u = hash_to_field(msg, dst, 2, self.curve.a2.field())?;
+        let q0 = self.map_to_curve(&u[0])?;
+        let q1 = self.map_to_curve(&u[1])?;
+        Ok(q0.checked_add(&q1)?.mul(cofactor(&self.curve)))
+    }
+
+    /// encode_to_curve from RFC 9380: a single field element. Faster, but the output isn't
+    /// uniformly distributed: only about half the points can be reached.
+    pub fn encode_to_curve(&self, msg: &[u8], dst: &[u8]) -> Result<Point, ECCError> {
+        let u = hash_to_field(msg, dst, 1, self.curve.a2.field())?;
+        Ok(self.map_to_curve(&u[0])?.mul(cofactor(&self.curve)))
+    }
+}
+
+// RFC 9380, appendix F.2.1.1: sqrt_ratio(u, v) is (true, sqrt(u/v)) if u/v is a square, and
+// (false, sqrt(Z.u/v)) otherwise, for a non-square Z (and (false, 0) for u = 0). Like Tonelli-Shanks,
+// but the loop always runs c1 - 1 times, for p - 1 = 2^c1.c2 with c2 odd.
+#[derive(Debug, Clone)]
+struct SqrtRatio {
+    c1: u64,
+    c3: BigUint,
+    c4: BigUint,
+    c5: BigUint,
+    // Z^c2 and Z^((c2 + 1) / 2)
+    c6: Element,
+    c7: Element,
+}
+
+impl SqrtRatio {
+    fn new(z: &Element) -> Self {
+        let p_minus_one = z.modulus() - buint(1);
+        let c1 = p_minus_one.trailing_zeros().unwrap();
+        let c2 = p_minus_one >> c1;
+        Self {
+            c1,
+            c3: (c2.clone() - buint(1)) >> 1,
+            c4: (buint(1) << c1) - buint(1),
+            c5: buint(1) << (c1 - 1),
+            c6: z.pow_big(&c2),
+            c7: z.pow_big(&((c2 + buint(1)) >> 1)),
+        }
+    }
+
+    fn compute(&self, u: &Element, v: &Element) -> (bool, Element) {
+        let one = Element::in_field(1, u.field());
+        let bit = |flag: bool| Element::in_field(u8::from(flag), u.field());
+
+        let mut tv1 = self.c6.clone();
+        let tv2 = v.pow_big(&self.c4);
+        let tv3 = tv2.pow(2) * v.clone();
+        let tv5 = (u.clone() * tv3).pow_big(&self.c3) * tv2;
+        let tv2 = tv5.clone() * v.clone();
+        let mut tv3 = tv5 * u.clone();
+        let mut tv4 = tv3.clone() * tv2;
+        let is_square = tv4.pow_big(&self.c5) == one;
+        tv3 = cmov(&(tv3.clone() * self.c7.clone()), &tv3, &bit(is_square));
+        tv4 = cmov(&(tv4.clone() * tv1.clone()), &tv4, &bit(is_square));
+        for k in (2..=self.c1).rev() {
+            let e1 = bit(tv4.pow_big(&(buint(1) << (k - 2))) == one);
+            let tv2 = tv3.clone() * tv1.clone();
+            tv1 = tv1.pow(2);
+            let tv5 = tv4.clone() * tv1.clone();
+            tv3 = cmov(&tv2, &tv3, &e1);
+            tv4 = cmov(&tv5, &tv4, &e1);
+        }
+        (is_square, tv3)
+    }
+}
+
+fn cofactor(curve: &Curve) -> BigUint {
+    curve.cofactor().cloned().unwrap_or(buint(1))
+}
+
+// b if c = 1, a if c = 0
+fn cmov(a: &Element, b: &Element, c: &Element) -> Element {
+    a.clone() + c.clone() * (b.clone() - a.clone())
+}
+
+// 1/x, and 0 for x = 0: x^(p-2)
+fn inv0(x: &Element) -> Element {
+    x.pow_big(&(x.modulus() - buint(2)))
+}
+
+// The "sign" of x: its parity
+fn sgn0(x: &Element) -> bool {
+    x.value().bit(0)
+}
+
+// 3x^4 + 6.a4.x^2 + 12.a6.x - a4^2: its roots are the x-coordinates of the points of order 3
+fn division_polynomial_3(curve: &Curve) -> Result<Vec<Element>, ECCError> {
+    let (a, b) = (curve.a4.clone(), curve.a6.clone());
+    let psi3 = Polynomial::from_elements(
+        vec![
+            -a.pow(2),
+            curve.element(12) * b,
+            curve.element(6) * a,
+            curve.zero(),
+            curve.element(3),
+        ],
+        curve.a4.field(),
+    )?;
+    Ok(psi3.roots())
+}
+
+// For a curve E with a4.a6 = 0: a 3-isogenous E' with a4'.a6' != 0, which has a 3-isogeny back to E
+fn find_isogenous_curve(curve: &Curve) -> Result<Curve, ECCError> {
+    for x0 in division_polynomial_3(curve)? {
+        let e1 = Isogeny::velu(curve, &x0)?.codomain().clone();
+        if (e1.a4.clone() * e1.a6.clone()).is_zero() {
+            continue;
+        }
+        if find_isogeny(&e1, curve)?.is_some() {
+            return Ok(e1);
+        }
+    }
+    Err(ECCError::NoIsogeny)
+}
+
+// A 3-isogeny E' -> E'', and the scaling s such that (x, y) -> (s^2.x, s^3.y) sends E'' to E.
+// When E' comes from Vélu's formulas, E' -> E'' is the dual isogeny, and E'' is E scaled by 3.
+fn find_isogeny(target: &Curve, curve: &Curve) -> Result<Option<(Isogeny, Element)>, ECCError> {
+    let three = curve.element(3);
+    for x1 in division_polynomial_3(target)? {
+        let back = Isogeny::velu(target, &x1)?;
+        let e2 = back.codomain().clone();
+        for s in [three.clone(), curve.element(1) / three.clone()] {
+            if s.pow(4) * e2.a4.clone() == curve.a4 && s.pow(6) * e2.a6.clone() == curve.a6 {
+                return Ok(Some((back, s)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+// RFC 9380, appendix H.2: the first of 1, -1, 2, -2... which is a non-square other than -1, such
+// that x^3 + a4.x + a6 - Z has no root, and g(a6 / (Z.a4)) is a square
+fn find_z(curve: &Curve) -> Result<Element, ECCError> {
+    let (a, b) = (curve.a4.clone(), curve.a6.clone());
+    let mut counter = buint(1);
+    while counter < curve.p {
+        for z in [
+            curve.element(bint(counter.clone())),
+            -curve.element(bint(counter.clone())),
+        ] {
+            if z.legendre() != -1 || z == -curve.element(1) {
+                continue;
+            }
+            let g_minus_z = Polynomial::from_elements(
+                vec![
+                    b.clone() - z.clone(),
+                    a.clone(),
+                    curve.zero(),
+                    curve.element(1),
+                ],
+                curve.a4.field(),
+            )?;
+            if !g_minus_z.roots().is_empty() {
+                continue;
+            }
+            if curve.rhs(&(b.clone() / (z.clone() * a.clone()))).legendre() != -1 {
+                return Ok(z);
+            }
+        }
+        counter += buint(1);
+    }
+    Err(ECCError::NoSswuParameter)
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+
+    use crate::element::Element;
+    use crate::hash_to_curve::{
+        division_polynomial_3, expand_message_xmd, hash_to_field, try_and_increment, Isogeny,
+        SqrtRatio, Sswu,
+    };
+    use crate::util::bint;
+    use crate::{Curve, ECCError};
+
+    fn hex(s: &str) -> BigUint {
+        BigUint::parse_bytes(s.as_bytes(), 16).unwrap()
+    }
+
+    fn hex_bytes(s: &str) -> Vec<u8> {
+        hex(s).to_bytes_be()
+    }
+
+    #[test]
+    fn test_expand_message_xmd() {
+        // RFC 9380, appendix K.1
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        let vectors = [
+            (
+                "",
+                "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235",
+            ),
+            (
+                "abc",
+                "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615",
+            ),
+        ];
+        for (msg, expected) in vectors {
+            let uniform_bytes = expand_message_xmd(msg.as_bytes(), dst, 32).unwrap();
+            assert_eq!(uniform_bytes, hex_bytes(expected), "{msg:?}");
+        }
+        assert_eq!(expand_message_xmd(b"abc", dst, 1000).unwrap().len(), 1000);
+        assert!(matches!(
+            expand_message_xmd(b"abc", dst, 256 * 32),
+            Err(ECCError::InvalidExpandLength)
+        ));
+    }
+
+    #[test]
+    fn test_p256_hash_to_curve() {
+        // RFC 9380, appendix J.1.1: P256_XMD:SHA-256_SSWU_RO_
+        let dst = b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_RO_";
+        let curve = Curve::named("P-256").unwrap();
+        let sswu = Sswu::new(&curve).unwrap();
+        assert_eq!(*sswu.z(), curve.element(-10));
+
+        let u = hash_to_field(b"", dst, 2, curve.a2.field()).unwrap();
+        assert_eq!(
+            u[0].value(),
+            hex("ad5342c66a6dd0ff080df1da0ea1c04b96e0330dd89406465eeba11582515009")
+        );
+        assert_eq!(
+            u[1].value(),
+            hex("8c0f1d43204bd6f6ea70ae8013070a1518b43873bcd850aafa0a9e220e2eea5a")
+        );
+
+        let vectors = [
+            (
+                "",
+                "2c15230b26dbc6fc9a37051158c95b79656e17a1a920b11394ca91c44247d3e4",
+                "8a7a74985cc5c776cdfe4b1f19884970453912e9d31528c060be9ab5c43e8415",
+            ),
+            (
+                "abc",
+                "0bb8b87485551aa43ed54f009230450b492fead5f1cc91658775dac4a3388a0f",
+                "5c41b3d0731a27a7b14bc0bf0ccded2d8751f83493404c84a88e71ffd424212e",
+            ),
+        ];
+        for (msg, x, y) in vectors {
+            let point = sswu.hash_to_curve(msg.as_bytes(), dst).unwrap();
+            assert_eq!(point, curve.point(bint(hex(x)), bint(hex(y))), "{msg:?}");
+        }
+    }
+
+    #[test]
+    fn test_sqrt_ratio() {
+        // 97 - 1 = 2^5 * 3 and 73 - 1 = 2^3 * 9 exercise the loop, which p = 3 (mod 4) skips
+        for p in [97u32, 73, 13] {
+            let z = (1..p)
+                .map(|z| Element::new(z, p))
+                .find(|z| z.legendre() == -1)
+                .unwrap();
+            let sqrt_ratio = SqrtRatio::new(&z);
+            for u in 0..p {
+                for v in 1..p {
+                    let (u, v) = (Element::new(u, p), Element::new(v, p));
+                    let (is_square, y) = sqrt_ratio.compute(&u, &v);
+                    let ratio = u.clone() / v.clone();
+                    // For u = 0, both answers hold, and the RFC's algorithm says false
+                    assert_eq!(is_square, ratio.legendre() == 1);
+                    let expected = if is_square { ratio } else { z.clone() * ratio };
+                    assert_eq!(y.pow(2), expected, "p = {p}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_velu_isogeny() {
+        // Y^2 = X^3 + 3X + 8 on F13: 9 points, 3 of which ({O, (9, 6), (9, 7)}) have order dividing 3
+        let curve = Curve::named("itmc-13").unwrap();
+        assert_eq!(
+            division_polynomial_3(&curve).unwrap(),
+            vec![curve.element(9)]
+        );
+        let isogeny = Isogeny::velu(&curve, &curve.element(9)).unwrap();
+        let codomain = isogeny.codomain();
+        assert!(codomain.is_smooth());
+
+        let g = curve.generator().unwrap();
+        let image = isogeny.map(&g).unwrap();
+        assert!(codomain.contains(image.clone()));
+        // A group morphism, whose kernel is the 3-torsion
+        for k in 0u32..9 {
+            let expected = image.mul(k.into());
+            assert_eq!(isogeny.map(&g.mul(k.into())).unwrap(), expected);
+        }
+        assert!(isogeny.map(&g.mul(3u32.into())).unwrap().is_at_infinity());
+
+        assert!(matches!(
+            Isogeny::velu(&curve, &curve.element(2)),
+            Err(ECCError::NotTorsionPoint)
+        ));
+    }
+
+    #[test]
+    fn test_secp256k1_hash_to_curve() {
+        // RFC 9380, appendix J.8.1: secp256k1_XMD:SHA-256_SSWU_RO_, with the 3-isogenous curve
+        // of appendix E.1
+        let dst = b"QUUX-V01-CS02-with-secp256k1_XMD:SHA-256_SSWU_RO_";
+        let curve = Curve::named("secp256k1").unwrap();
+        let target = Curve::new(
+            "secp256k1 (3-isogenous, RFC 9380)",
+            bint(0),
+            bint(hex(
+                "3f8731abdd661adca08a5558f0f5d272e953d363cb6f0e5d405447c01a444533",
+            )),
+            bint(1771),
+            curve.p.clone(),
+        );
+        let sswu = Sswu::with_isogenous_curve(&curve, &target).unwrap();
+        assert_eq!(*sswu.z(), curve.element(-11));
+
+        let vectors = [
+            (
+                "",
+                "c1cae290e291aee617ebaef1be6d73861479c48b841eaba9b7b5852ddfeb1346",
+                "64fa678e07ae116126f08b022a94af6de15985c996c3a91b64c406a960e51067",
+            ),
+            (
+                "abc",
+                "3377e01eab42db296b512293120c6cee72b6ecf9f9205760bd9ff11fb3cb2c4b",
+                "7f95890f33efebd1044d382a01b1bee0900fb6116f94688d487c6c7b9c8371f6",
+            ),
+            (
+                "abcdef0123456789",
+                "bac54083f293f1fe08e4a70137260aa90783a5cb84d3f35848b324d0674b0e3a",
+                "4436476085d4c3c4508b60fcf4389c40176adce756b398bdee27bca19758d828",
+            ),
+        ];
+        for (msg, x, y) in vectors {
+            let point = sswu.hash_to_curve(msg.as_bytes(), dst).unwrap();
+            assert_eq!(point, curve.point(bint(hex(x)), bint(hex(y))), "{msg:?}");
+        }
+    }
+
+    #[test]
+    fn test_sswu_with_isogeny() {
+        // secp256k1 has a4 = 0. Without the RFC's isogenous curve, the points are valid, but
+        // don't match other implementations: hence a DST of our own.
+        let curve = Curve::named("secp256k1").unwrap();
+        let sswu = Sswu::new(&curve).unwrap();
+        assert_ne!(sswu.target(), &curve);
+        let dst = b"toy_ecc-secp256k1_XMD:SHA-256_SSWU_RO_";
+        for msg in [&b""[..], b"abc", b"abcdef0123456789"] {
+            let point = sswu.hash_to_curve(msg, dst).unwrap();
+            assert!(curve.contains(point.clone()));
+            assert_eq!(point, sswu.hash_to_curve(msg, dst).unwrap());
+            let encoded = sswu.encode_to_curve(msg, dst).unwrap();
+            assert!(curve.contains(encoded.clone()));
+            assert_ne!(point, encoded);
+        }
+    }
+
+    #[test]
+    fn test_sswu_on_toy_curves() {
+        // Every field element maps to a point of the curve. supersingular-59 has a6 = 0.
+        for name in ["itmc-631", "supersingular-59", "P-256"] {
+            let curve = Curve::named(name).unwrap();
+            let sswu = Sswu::new(&curve).unwrap();
+            for u in 0u32..50 {
+                let point = sswu.map_to_curve(&curve.element(u)).unwrap();
+                assert!(curve.contains(point), "{name}, u = {u}");
+            }
+        }
+        // F13 is too small: no Z satisfies all of the RFC's criteria
+        assert!(matches!(
+            Sswu::new(&Curve::named("itmc-13").unwrap()),
+            Err(ECCError::NoSswuParameter)
+        ));
+        assert!(matches!(
+            Sswu::new(&Curve::new("montgomery", 3, 1, 0, 13)),
+            Err(ECCError::NotShortWeierstrass)
+        ));
+    }
+
+    #[test]
+    fn test_try_and_increment() {
+        let dst = b"toy_ecc-try-and-increment";
+        for name in ["itmc-13", "supersingular-59", "secp256k1", "Curve25519"] {
+            let curve = Curve::named(name).unwrap();
+            let point = try_and_increment(&curve, b"hello", dst).unwrap();
+            assert!(curve.contains(point.clone()), "{name}");
+            // Cofactor cleared: the point is in the generator's subgroup
+            let n = curve.subgroup_order().unwrap();
+            assert!(point.mul(n.clone()).is_at_infinity(), "{name}");
+            assert_ne!(point, try_and_increment(&curve, b"hello!", dst).unwrap());
+        }
+    }
+}
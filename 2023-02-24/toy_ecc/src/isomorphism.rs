@@ -0,0 +1,248 @@
+//! Curves up to isomorphism, over F_p with p > 3.
+//!
+//! Any curve y^2 = x^3 + a2.x^2 + a4.x + a6 can be put in short Weierstrass form by completing the
+//! cube. Two short Weierstrass curves are then isomorphic over F_p iff there is a u in F_p* with
+//!     a4' = u^4.a4 and a6' = u^6.a6
+//! and (x, y) -> (u^2.x, u^3.y) is the isomorphism. Over the algebraic closure any u works, so the
+//! j-invariant is all that matters. Over F_p, curves with the same j-invariant can still differ:
+//! the quadratic twist by a non-square d has the same j-invariant, and #E + #E^d = 2p + 2.
+
+use crate::element::Element;
+use crate::util::buint;
+use crate::{Curve, ECCError, Point};
+
+impl Curve {
+    /// The quadratic twist by the smallest non-square d: see `quadratic_twist_by`.
+    pub fn quadratic_twist(&self) -> Curve {
+        self.quadratic_twist_by(&self.non_square())
+    }
+
+    /// d.y^2 = x^3 + a2.x^2 + a4.x + a6, i.e. after (x, y) -> (d.x, d^2.y):
+    ///     y^2 = x^3 + d.a2.x^2 + d^2.a4.x + d^3.a6
+    /// All non-squares d give isomorphic twists, and a square d gives a curve isomorphic to this
+    /// one. The twist doesn't come with a generator.
+    pub fn quadratic_twist_by(&self, d: &Element) -> Curve {
+        Curve::new(
+            &format!("{} (twist)", self.name),
+            (d.clone() * self.a2.clone()).value(),
+            (d.pow(2) * self.a4.clone()).value(),
+            (d.pow(3) * self.a6.clone()).value(),
+            self.p.clone(),
+        )
+    }
+
+    // The smallest non-square in F_p, for odd p
+    pub(crate) fn non_square(&self) -> Element {
+        let mut d = self.element(2);
+        while d.legendre() != -1 {
+            d = d + self.element(1);
+        }
+        d
+    }
+
+    /// The isomorphic curve y^2 = x^3 + a4'.x + a6', with x' = x + a2/3:
+    ///     a4' = a4 - a2^2/3
+    ///     a6' = a6 - a2.a4/3 + 2.a2^3/27
+    /// The generator, if any, is mapped too. Errors with `NotInvertible` if p = 3.
+    pub fn to_short_weierstrass(&self) -> Result<Curve, ECCError> {
+        if self.a2.is_zero() {
+            return Ok(self.clone());
+        }
+        let s = self.a2.checked_div(&self.element(3))?;
+        let a4 = self.a4.clone() - self.element(3) * s.pow(2);
+        let a6 = self.a6.clone() - self.a4.clone() * s.clone() + self.element(2) * s.pow(3);
+        let curve = Curve::new(&self.name, buint(0), a4.value(), a6.value(), self.p.clone());
+        Ok(match (self.generator(), &self.order, &self.cofactor) {
+            (Some(g), Some(order), Some(cofactor)) => curve.with_generator_unchecked(
+                (g.x + s).value(),
+                g.y.value(),
+                order.clone(),
+                cofactor.clone(),
+            ),
+            _ => curve,
+        })
+    }
+
+    /// Whether there is an isomorphism (x, y) -> (u^2.x + r, u^3.y) from this curve to `other`,
+    /// defined over F_p. Singular curves aren't isomorphic to anything.
+    pub fn is_isomorphic(&self, other: &Curve) -> bool {
+        if self.p != other.p || !self.is_smooth() || !other.is_smooth() {
+            return false;
+        }
+        let (Ok(e1), Ok(e2)) = (self.to_short_weierstrass(), other.to_short_weierstrass()) else {
+            return false;
+        };
+        let (a, b) = (e1.a4.clone(), e1.a6.clone());
+        let (a_, b_) = (e2.a4.clone(), e2.a6.clone());
+        if a.is_zero() != a_.is_zero() || b.is_zero() != b_.is_zero() {
+            // Different j-invariants: j = 0 iff a = 0, j = 1728 iff b = 0
+            return false;
+        }
+
+        if a.is_zero() {
+            // j = 0: any u with u^6 = b'/b
+            is_power(&(b_ / b), 6)
+        } else if b.is_zero() {
+            // j = 1728: any u with u^4 = a'/a
+            is_power(&(a_ / a), 4)
+        } else {
+            // With λ = u^2: a' = λ^2.a and b' = λ^3.b, so λ = a.b' / (a'.b), and it must be a square
+            let lambda = a.clone() * b_.clone() / (a_.clone() * b.clone());
+            lambda.pow(2) * a == a_ && lambda.pow(3) * b == b_ && lambda.legendre() == 1
+        }
+    }
+}
+
+impl Point {
+    /// This point on `to_short_weierstrass()` of its curve: (x + a2/3, y).
+    pub fn to_short_weierstrass(&self) -> Result<Point, ECCError> {
+        let target = self.curve.to_short_weierstrass()?;
+        if self.is_at_infinity() {
+            return Ok(target.point_at_infinity());
+        }
+        let s = self.curve.a2.checked_div(&self.curve.element(3))?;
+        target.point_from_elements(self.x.clone() + s, self.y.clone())
+    }
+}
+
+// Whether x = u^k for some u in F_p*: x^((p - 1) / gcd(k, p - 1)) = 1
+fn is_power(x: &Element, k: u32) -> bool {
+    let p_minus_1 = x.modulus() - buint(1);
+    let gcd = (1..=k)
+        .rev()
+        .find(|&d| k.is_multiple_of(d) && (&p_minus_1 % d) == buint(0))
+        .unwrap();
+    !x.is_zero() && x.pow_big(&(p_minus_1 / gcd)) == Element::new(1, x.modulus().clone())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::util::buint;
+    use crate::{Curve, ECCError};
+
+    #[test]
+    fn test_quadratic_twist() {
+        // #E + #E^d = 2p + 2
+        for (name, order, twist_order) in [("itmc-13", 9u32, 19u32), ("itmc-631", 650, 614)] {
+            let curve = Curve::named(name).unwrap();
+            let twist = curve.quadratic_twist();
+            assert_eq!(curve.order().unwrap(), buint(order));
+            assert_eq!(twist.order().unwrap(), buint(twist_order));
+            assert_eq!(twist.j_invariant().unwrap(), curve.j_invariant().unwrap());
+            assert!(!twist.is_isomorphic(&curve));
+            // Twisting twice gives back the original curve, up to isomorphism
+            assert!(twist.quadratic_twist().is_isomorphic(&curve));
+        }
+    }
+
+    // Isomorphic iff some u in 1..p gives a' = u^4.a and b' = u^6.b
+    fn brute_force_isomorphic(p: u32, (a, b): (u32, u32), (a_, b_): (u32, u32)) -> bool {
+        (1..p as u64).any(|u| {
+            let u2 = u * u % p as u64;
+            let u4 = u2 * u2 % p as u64;
+            let u6 = u4 * u2 % p as u64;
+            a_ as u64 == a as u64 * u4 % p as u64 && b_ as u64 == b as u64 * u6 % p as u64
+        })
+    }
+
+    #[test]
+    fn test_is_isomorphic_matches_brute_force() {
+        // p = 13 has all sixth and fourth roots of unity, p = 11 has neither: j = 0 and j = 1728
+        // behave differently
+        for p in [11u32, 13] {
+            for (a, b) in [(0, 1), (0, 2), (1, 0), (2, 0), (3, 8), (1, 1)] {
+                let curve = Curve::new("E", 0, a, b, p);
+                for a_ in 0..p {
+                    for b_ in 0..p {
+                        let other = Curve::new("E'", 0, a_, b_, p);
+                        if !other.is_smooth() {
+                            assert!(!curve.is_isomorphic(&other));
+                            continue;
+                        }
+                        assert_eq!(
+                            curve.is_isomorphic(&other),
+                            brute_force_isomorphic(p, (a, b), (a_, b_)),
+                            "{curve} and {other}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_isomorphic_curves_have_the_same_order() {
+        let curve = Curve::named("itmc-631").unwrap();
+        let u = curve.element(5);
+        let scaled = Curve::new(
+            "scaled",
+            buint(0),
+            (u.pow(4) * curve.a4.clone()).value(),
+            (u.pow(6) * curve.a6.clone()).value(),
+            curve.p.clone(),
+        );
+        assert!(curve.is_isomorphic(&scaled));
+        assert_eq!(scaled.order().unwrap(), curve.order().unwrap());
+
+        let singular = Curve::new("node", 0, -3, 2, 631);
+        assert!(!singular.is_isomorphic(&singular));
+        assert!(!curve.is_isomorphic(&Curve::named("itmc-13").unwrap()));
+    }
+
+    #[test]
+    fn test_to_short_weierstrass() {
+        // Curve25519 in Montgomery form, y^2 = x^3 + 486662x^2 + x, with u = 9 as its base point,
+        // is the registry's Wei25519
+        let wei25519 = Curve::named("Curve25519").unwrap();
+        let g = wei25519.generator().unwrap();
+        let montgomery = Curve::new(
+            "Curve25519",
+            buint(486662),
+            buint(1),
+            buint(0),
+            wei25519.p.clone(),
+        )
+        .with_generator(
+            buint(9),
+            g.y.value(),
+            wei25519.subgroup_order().unwrap().clone(),
+            buint(8),
+        )
+        .unwrap();
+        assert_eq!(montgomery.to_short_weierstrass().unwrap(), wei25519);
+        assert_eq!(
+            montgomery
+                .generator()
+                .unwrap()
+                .to_short_weierstrass()
+                .unwrap(),
+            g
+        );
+        assert!(montgomery.is_isomorphic(&wei25519));
+        assert_eq!(
+            montgomery.j_invariant().unwrap(),
+            wei25519.j_invariant().unwrap()
+        );
+
+        // The map preserves the group law
+        let toy = Curve::new("toy", 3, 1, 0, 13);
+        let short = toy.to_short_weierstrass().unwrap();
+        assert!(short.is_smooth());
+        let p = toy.point(2, 3);
+        assert!(toy.contains(p.clone()));
+        let q = p.clone() + p.clone();
+        assert_eq!(
+            q.to_short_weierstrass().unwrap(),
+            p.to_short_weierstrass().unwrap() + p.to_short_weierstrass().unwrap()
+        );
+        assert!(short.contains(q.to_short_weierstrass().unwrap()));
+
+        // Short Weierstrass curves are left alone
+        assert_eq!(short.to_short_weierstrass().unwrap(), short);
+
+        assert!(matches!(
+            Curve::new("p = 3", 1, 1, 1, 3).to_short_weierstrass(),
+            Err(ECCError::NotInvertible)
+        ));
+    }
+}
@@ -0,0 +1,304 @@
+use std::fmt;
+use std::ops;
+
+use num_bigint::BigUint;
+
+use crate::element::{Element, FieldElement};
+use crate::util::buint;
+use crate::{Curve, ECCError, Point};
+
+/// A point in Jacobian coordinates: (X, Y, Z) represents the affine point (X/Z^2, Y/Z^3).
+/// The point at infinity is any point with Z = 0.
+///
+/// Adding and doubling Jacobian points doesn't require any field inversion. The price to pay is a
+/// handful of extra multiplications, and a single inversion when converting back to affine.
+#[derive(Debug, Clone)]
+pub struct JacobianPoint<F = Element> {
+    x: F,
+    y: F,
+    z: F,
+    curve: Curve<F>,
+}
+
+impl<F: FieldElement> JacobianPoint<F> {
+    pub fn infinity(curve: &Curve<F>) -> Self {
+        Self {
+            x: curve.element(1),
+            y: curve.element(1),
+            z: curve.zero(),
+            curve: curve.clone(),
+        }
+    }
+
+    pub fn is_at_infinity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    /// Converts back to affine coordinates. This costs one field inversion.
+    pub fn to_affine(&self) -> Point<F> {
+        if self.is_at_infinity() {
+            return self.curve.point_at_infinity();
+        }
+        let z_inv = self.curve.element(1) / self.z.clone();
+        let z_inv_squared = z_inv.pow(2);
+        Point {
+            x: self.x.clone() * z_inv_squared.clone(),
+            y: self.y.clone() * z_inv_squared * z_inv,
+            infinity: false,
+            curve: self.curve.clone(),
+        }
+    }
+
+    /// Point doubling on y^2 = x^3 + a2.x^2 + a4.x + a6, in Jacobian coordinates:
+    ///     M = 3X^2 + 2.a2.X.Z^2 + a4.Z^4
+    ///     S = 4.X.Y^2
+    ///     X' = M^2 - 2S - 4.a2.Y^2.Z^2
+    ///     Y' = M(S - X') - 8Y^4
+    ///     Z' = 2.Y.Z
+    pub fn double(&self) -> Self {
+        if self.is_at_infinity() || self.y.is_zero() {
+            return Self::infinity(&self.curve);
+        }
+        let c = self.curve.clone();
+        let (x, y, z) = (self.x.clone(), self.y.clone(), self.z.clone());
+
+        let y_squared = y.pow(2);
+        let z_squared = z.pow(2);
+        let m = c.element(3) * x.pow(2)
+            + c.element(2) * c.a2.clone() * x.clone() * z_squared.clone()
+            + c.a4.clone() * z_squared.pow(2);
+        let s = c.element(4) * x * y_squared.clone();
+
+        let result_x = m.pow(2)
+            - c.element(2) * s.clone()
+            - c.element(4) * c.a2.clone() * y_squared.clone() * z_squared;
+        let result_y = m * (s - result_x.clone()) - c.element(8) * y_squared.pow(2);
+        let result_z = c.element(2) * y * z;
+
+        Self {
+            x: result_x,
+            y: result_y,
+            z: result_z,
+            curve: c,
+        }
+    }
+
+    // Scalar multiplication with the double-and-add technique, without any field inversion.
+    pub fn mul(&self, scalar: BigUint) -> Self {
+        let mut result = Self::infinity(&self.curve);
+        let mut multiplier = scalar;
+        let mut doubled_point = self.clone();
+        while multiplier != buint(0) {
+            if multiplier.bit(0) {
+                result = result + doubled_point.clone();
+            }
+            doubled_point = doubled_point.double();
+            multiplier >>= 1;
+        }
+        result
+    }
+}
+
+impl<F: FieldElement> From<Point<F>> for JacobianPoint<F> {
+    fn from(point: Point<F>) -> Self {
+        if point.is_at_infinity() {
+            return Self::infinity(&point.curve);
+        }
+        Self {
+            x: point.x,
+            y: point.y,
+            z: point.curve.element(1),
+            curve: point.curve,
+        }
+    }
+}
+
+impl<F: FieldElement> From<JacobianPoint<F>> for Point<F> {
+    fn from(point: JacobianPoint<F>) -> Self {
+        point.to_affine()
+    }
+}
+
+impl fmt::Display for JacobianPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_at_infinity() {
+            write!(f, "<Jacobian Point at Infinity>")
+        } else {
+            write!(
+                f,
+                "<Jacobian Point on curve {}: X={}, Y={}, Z={}>",
+                self.curve.name,
+                self.x.value(),
+                self.y.value(),
+                self.z.value()
+            )
+        }
+    }
+}
+
+// Two Jacobian points are equal if they represent the same affine point:
+// X1.Z2^2 = X2.Z1^2 and Y1.Z2^3 = Y2.Z1^3
+impl<F: FieldElement> PartialEq for JacobianPoint<F> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.curve != other.curve {
+            return false;
+        }
+        if self.is_at_infinity() || other.is_at_infinity() {
+            return self.is_at_infinity() && other.is_at_infinity();
+        }
+        let z1_squared = self.z.pow(2);
+        let z2_squared = other.z.pow(2);
+        self.x.clone() * z2_squared.clone() == other.x.clone() * z1_squared.clone()
+            && self.y.clone() * z2_squared * other.z.clone()
+                == other.y.clone() * z1_squared * self.z.clone()
+    }
+}
+
+impl<F: FieldElement> Eq for JacobianPoint<F> {}
+
+impl<F: FieldElement> ops::Neg for JacobianPoint<F> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self {
+            x: self.x,
+            y: -self.y,
+            z: self.z,
+            curve: self.curve,
+        }
+    }
+}
+
+impl<F: FieldElement> ops::Add for JacobianPoint<F> {
+    type Output = Self;
+
+    /// Point addition on y^2 = x^3 + a2.x^2 + a4.x + a6, in Jacobian coordinates:
+    ///     U1 = X1.Z2^2, U2 = X2.Z1^2, S1 = Y1.Z2^3, S2 = Y2.Z1^3
+    ///     H = U2 - U1, R = S2 - S1
+    ///     X' = R^2 - H^3 - 2.U1.H^2 - a2.Z'^2
+    ///     Y' = R(U1.H^2 - X') - S1.H^3
+    ///     Z' = H.Z1.Z2
+    fn add(self, other: Self) -> Self {
+        if other.curve != self.curve {
+            panic!("{}", ECCError::CurveMismatch)
+        }
+        if self.is_at_infinity() {
+            return other;
+        }
+        if other.is_at_infinity() {
+            return self;
+        }
+        let c = self.curve.clone();
+
+        let z1_squared = self.z.pow(2);
+        let z2_squared = other.z.pow(2);
+        let u1 = self.x.clone() * z2_squared.clone();
+        let u2 = other.x.clone() * z1_squared.clone();
+        let s1 = self.y.clone() * z2_squared * other.z.clone();
+        let s2 = other.y.clone() * z1_squared * self.z.clone();
+
+        let h = u2 - u1.clone();
+        let r = s2 - s1.clone();
+        if h.is_zero() {
+            // Same x coordinate: either P + P or P + -P
+            return if r.is_zero() {
+                self.double()
+            } else {
+                Self::infinity(&c)
+            };
+        }
+
+        let h_squared = h.pow(2);
+        let h_cubed = h_squared.clone() * h.clone();
+        let u1_h_squared = u1 * h_squared;
+        let result_z = h * self.z * other.z;
+
+        let result_x = r.pow(2)
+            - h_cubed.clone()
+            - c.element(2) * u1_h_squared.clone()
+            - c.a2.clone() * result_z.pow(2);
+        let result_y = r * (u1_h_squared - result_x.clone()) - s1 * h_cubed;
+
+        Self {
+            x: result_x,
+            y: result_y,
+            z: result_z,
+            curve: c,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::jacobian::JacobianPoint;
+    use crate::util::buint;
+    use crate::Curve;
+
+    #[test]
+    fn test_roundtrip_affine() {
+        let c = Curve::new("trivial curve", 0, 3, 8, 13);
+        let p = c.point(9, 6);
+        let j = JacobianPoint::from(p.clone());
+        assert_eq!(j.to_affine(), p);
+
+        let inf = JacobianPoint::from(c.point_at_infinity());
+        assert!(inf.is_at_infinity());
+        assert!(inf.to_affine().is_at_infinity());
+    }
+
+    #[test]
+    fn test_equality_ignores_scaling() {
+        let c = Curve::new("trivial curve", 0, 3, 8, 13);
+        let j = JacobianPoint::from(c.point(9, 6));
+        // (X, Y, Z) ~ (l^2.X, l^3.Y, l.Z)
+        let l = c.element(5);
+        let scaled = JacobianPoint {
+            x: j.x.clone() * l.pow(2),
+            y: j.y.clone() * l.pow(3),
+            z: j.z.clone() * l,
+            curve: c.clone(),
+        };
+        assert_eq!(j, scaled);
+        assert_eq!(scaled.to_affine(), c.point(9, 6));
+    }
+
+    #[test]
+    fn test_add_and_double_match_affine() {
+        // One curve per form: Weierstrass, "Montgomery" (a4 = 0) and a2, a4, a6 all non-zero
+        for (c, p) in [
+            (Curve::new("weierstrass", 0, 3, 8, 13), 13),
+            (Curve::new("montgomery", 5, 0, 3, 17), 17),
+            (Curve::new("unknown", 2, 3, 5, 19), 19),
+        ] {
+            let mut points = vec![c.point_at_infinity()];
+            for x in 0..p {
+                for y in 0..p {
+                    let q = c.point(x, y);
+                    if !q.is_at_infinity() && c.contains(q.clone()) {
+                        points.push(q);
+                    }
+                }
+            }
+            for a in &points {
+                let ja = JacobianPoint::from(a.clone());
+                assert_eq!(ja.double().to_affine(), a.clone() + a.clone());
+                for b in &points {
+                    let jb = JacobianPoint::from(b.clone());
+                    assert_eq!((ja.clone() + jb).to_affine(), a.clone() + b.clone());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_on_secp256k1() {
+        let g = Curve::named("secp256k1").unwrap().generator().unwrap();
+        let scalar = buint(0xdeadbeefu32);
+        assert_eq!(
+            JacobianPoint::from(g.clone())
+                .mul(scalar.clone())
+                .to_affine(),
+            g.mul_affine(scalar)
+        );
+    }
+}
@@ -0,0 +1,325 @@
+//! Scalar multiplication for secret scalars, without secret-dependent branches.
+//!
+//! `Point::mul` leaks its scalar through timing in three ways: it only adds when a bit is set, it
+//! stops after the most significant bit, and the addition formulas special-case O, P + P and P - P.
+//! `mul_ct` fixes all three:
+//!   - a Montgomery ladder does one addition and one doubling per bit, whatever the bit
+//!   - the number of iterations is the bit length of the group order, not of the scalar
+//!   - points are in projective coordinates (X:Y:Z), with the complete formulas of Renes, Costello
+//!     and Batina (https://eprint.iacr.org/2015/1060, algorithm 1). They handle O = (0:1:0),
+//!     doublings and opposite points without any branch. Their only exceptions involve points of
+//!     order 2, so they're complete within any subgroup of odd order.
+//!   - the ladder's swaps are arithmetic: a + bit.(b - a), instead of an `if`
+//!
+//! This removes the leaks from the algorithm, not from the arithmetic: `BigUint` operations still
+//! take variable time. A real implementation would use fixed-size limbs all the way down.
+
+use num_bigint::BigUint;
+
+use crate::element::FieldElement;
+use crate::{Curve, ECCError, Point};
+
+// (X:Y:Z) represents the affine point (X/Z, Y/Z). The point at infinity is (0:1:0).
+#[derive(Clone)]
+struct ProjectivePoint<F> {
+    x: F,
+    y: F,
+    z: F,
+}
+
+impl<F: FieldElement> ProjectivePoint<F> {
+    fn from_affine(point: &Point<F>) -> Self {
+        let curve = &point.curve;
+        if point.is_at_infinity() {
+            Self::infinity(curve)
+        } else {
+            Self {
+                x: point.x.clone(),
+                y: point.y.clone(),
+                z: curve.element(1),
+            }
+        }
+    }
+
+    fn infinity(curve: &Curve<F>) -> Self {
+        Self {
+            x: curve.zero(),
+            y: curve.element(1),
+            z: curve.zero(),
+        }
+    }
+
+    fn to_affine(&self, curve: &Curve<F>) -> Point<F> {
+        if self.z.is_zero() {
+            return curve.point_at_infinity();
+        }
+        let z_inv = curve.element(1) / self.z.clone();
+        Point {
+            x: self.x.clone() * z_inv.clone(),
+            y: self.y.clone() * z_inv,
+            infinity: false,
+            curve: curve.clone(),
+        }
+    }
+
+    // Complete addition on y^2 = x^3 + a.x + b, with b3 = 3b: 12 multiplications, 3 by a, 2 by b3
+    fn add(&self, other: &Self, a: &F, b3: &F) -> Self {
+        let (x1, y1, z1) = (&self.x, &self.y, &self.z);
+        let (x2, y2, z2) = (&other.x, &other.y, &other.z);
+
+        let t0 = x1.clone() * x2.clone();
+        let t1 = y1.clone() * y2.clone();
+        let t2 = z1.clone() * z2.clone();
+        // X1.Y2 + X2.Y1, X1.Z2 + X2.Z1 and Y1.Z2 + Y2.Z1, with one multiplication each
+        let t3 = (x1.clone() + y1.clone()) * (x2.clone() + y2.clone()) - (t0.clone() + t1.clone());
+        let t4 = (x1.clone() + z1.clone()) * (x2.clone() + z2.clone()) - (t0.clone() + t2.clone());
+        let t5 = (y1.clone() + z1.clone()) * (y2.clone() + z2.clone()) - (t1.clone() + t2.clone());
+
+        let z3 = a.clone() * t4.clone() + b3.clone() * t2.clone();
+        let x3 = t1.clone() - z3.clone();
+        let z3 = t1 + z3;
+        let y3 = x3.clone() * z3.clone();
+
+        let t1 = t0.clone() + t0.clone() + t0.clone() + a.clone() * t2.clone();
+        let t2 = a.clone() * (t0 - a.clone() * t2);
+        let t4 = b3.clone() * t4 + t2;
+
+        Self {
+            x: t3.clone() * x3 - t5.clone() * t4.clone(),
+            y: y3 + t1.clone() * t4,
+            z: t5 * z3 + t3 * t1,
+        }
+    }
+
+    // Swaps the points if bit = 1, leaves them alone if bit = 0, with the same operations either way
+    fn conditional_swap(&mut self, other: &mut Self, bit: &F) {
+        for (u, v) in [
+            (&mut self.x, &mut other.x),
+            (&mut self.y, &mut other.y),
+            (&mut self.z, &mut other.z),
+        ] {
+            let delta = bit.clone() * (v.clone() - u.clone());
+            *u = u.clone() + delta.clone();
+            *v = v.clone() - delta;
+        }
+    }
+}
+
+impl<F: FieldElement> Point<F> {
+    /// [scalar]P with a Montgomery ladder over the bit length of `order`, for secret scalars.
+    /// The curve must be in short Weierstrass form (a2 = 0), and P in a subgroup of odd order.
+    /// Errors with `InvalidScalar` if scalar >= order.
+    pub fn mul_ct(&self, scalar: &BigUint, order: &BigUint) -> Result<Point<F>, ECCError> {
+        let curve = &self.curve;
+        if !curve.a2.is_zero() {
+            return Err(ECCError::NotShortWeierstrass);
+        }
+        if scalar >= order {
+            return Err(ECCError::InvalidScalar);
+        }
+        let a = &curve.a4;
+        let b3 = curve.element(3) * curve.a6.clone();
+
+        // Invariant: r1 = r0 + P
+        let mut r0 = ProjectivePoint::infinity(curve);
+        let mut r1 = ProjectivePoint::from_affine(self);
+        for i in (0..order.bits()).rev() {
+            let bit = curve.element(u8::from(scalar.bit(i)));
+            r0.conditional_swap(&mut r1, &bit);
+            r1 = r0.add(&r1, a, &b3);
+            r0 = r0.add(&r0, a, &b3);
+            r0.conditional_swap(&mut r1, &bit);
+        }
+        Ok(r0.to_affine(curve))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::ops;
+
+    use num_bigint::{BigUint, ToBigInt};
+    use proptest::prelude::*;
+
+    use crate::element::{Element, FieldElement};
+    use crate::util::buint;
+    use crate::{Curve, ECCError, Point};
+
+    thread_local! {
+        static TRACE: RefCell<Vec<&'static str>> = const { RefCell::new(vec![]) };
+    }
+
+    fn record(operation: &'static str) {
+        TRACE.with(|trace| trace.borrow_mut().push(operation));
+    }
+
+    // An `Element` which records every field operation done on it, in this thread
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct Traced(Element);
+
+    impl ops::Add for Traced {
+        type Output = Self;
+        fn add(self, other: Self) -> Self {
+            record("add");
+            Self(self.0 + other.0)
+        }
+    }
+
+    impl ops::Sub for Traced {
+        type Output = Self;
+        fn sub(self, other: Self) -> Self {
+            record("sub");
+            Self(self.0 - other.0)
+        }
+    }
+
+    impl ops::Mul for Traced {
+        type Output = Self;
+        fn mul(self, other: Self) -> Self {
+            record("mul");
+            Self(self.0 * other.0)
+        }
+    }
+
+    impl ops::Div for Traced {
+        type Output = Self;
+        fn div(self, other: Self) -> Self {
+            record("div");
+            Self(self.0 / other.0)
+        }
+    }
+
+    impl ops::Neg for Traced {
+        type Output = Self;
+        fn neg(self) -> Self {
+            record("neg");
+            Self(-self.0)
+        }
+    }
+
+    impl FieldElement for Traced {
+        fn embed<T: ToBigInt>(&self, value: T) -> Self {
+            record("embed");
+            Self(self.0.embed(value))
+        }
+
+        fn characteristic(&self) -> &BigUint {
+            self.0.characteristic()
+        }
+
+        fn field_order(&self) -> BigUint {
+            self.0.field_order()
+        }
+
+        fn is_zero(&self) -> bool {
+            record("is_zero");
+            self.0.is_zero()
+        }
+
+        fn pow(&self, exponent: usize) -> Self {
+            record("pow");
+            Self(self.0.pow(exponent))
+        }
+
+        fn pow_big(&self, exponent: &BigUint) -> Self {
+            record("pow_big");
+            Self(self.0.pow_big(exponent))
+        }
+
+        fn checked_add(&self, other: &Self) -> Result<Self, ECCError> {
+            record("add");
+            self.0.checked_add(&other.0).map(Self)
+        }
+
+        fn checked_sub(&self, other: &Self) -> Result<Self, ECCError> {
+            record("sub");
+            self.0.checked_sub(&other.0).map(Self)
+        }
+
+        fn checked_mul(&self, other: &Self) -> Result<Self, ECCError> {
+            record("mul");
+            self.0.checked_mul(&other.0).map(Self)
+        }
+
+        fn checked_div(&self, other: &Self) -> Result<Self, ECCError> {
+            record("div");
+            self.0.checked_div(&other.0).map(Self)
+        }
+    }
+
+    // The field operations done by `mul_ct`, in order
+    fn mul_ct_trace(point: &Point<Traced>, scalar: &BigUint, order: &BigUint) -> Vec<&'static str> {
+        TRACE.with(|trace| trace.borrow_mut().clear());
+        point.mul_ct(scalar, order).unwrap();
+        TRACE.with(|trace| trace.take())
+    }
+
+    #[test]
+    fn test_mul_ct_on_toy_curve() {
+        // Y^2 = X^3 + 3X + 8 on F13 has 9 points: every scalar and every point, including O
+        let curve = Curve::named("itmc-13").unwrap();
+        let g = curve.generator().unwrap();
+        for i in 0u32..9 {
+            let p = g.mul(buint(i));
+            for k in 0u32..9 {
+                assert_eq!(p.mul_ct(&buint(k), &buint(9)).unwrap(), p.mul(buint(k)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_ct_errors() {
+        let curve = Curve::named("itmc-13").unwrap();
+        let g = curve.generator().unwrap();
+        assert!(matches!(
+            g.mul_ct(&buint(9), &buint(9)),
+            Err(ECCError::InvalidScalar)
+        ));
+        let montgomery = Curve::new("montgomery", 3, 1, 0, 13);
+        assert!(matches!(
+            montgomery.point(1, 8).mul_ct(&buint(2), &buint(9)),
+            Err(ECCError::NotShortWeierstrass)
+        ));
+    }
+
+    #[test]
+    fn test_mul_ct_operations() {
+        // Scalars with 2 and ~200 bits set go through the same field operations, in the same order.
+        // Wall-clock timings are in the benchmarks: they're too noisy for a test.
+        let curve = Curve::named("P-256").unwrap();
+        let g = curve.generator().unwrap();
+        let n = curve.subgroup_order().unwrap();
+        let low = (buint(1) << 255) + buint(1);
+        let high = n - buint(1);
+        assert!(high.count_ones() > 150);
+
+        let traced = Curve::from_elements(
+            "P-256 (traced)",
+            Traced(curve.a2.clone()),
+            Traced(curve.a4.clone()),
+            Traced(curve.a6.clone()),
+        )
+        .unwrap();
+        let g = traced
+            .point_from_elements(Traced(g.x), Traced(g.y))
+            .unwrap();
+
+        let low_trace = mul_ct_trace(&g, &low, n);
+        assert!(low_trace.iter().filter(|&&op| op == "mul").count() > 256 * 12);
+        assert_eq!(mul_ct_trace(&g, &high, n), low_trace);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn test_mul_ct_matches_mul(bytes in proptest::collection::vec(any::<u8>(), 32)) {
+            let curve = Curve::named("secp256k1").unwrap();
+            let g = curve.generator().unwrap();
+            let n = curve.subgroup_order().unwrap();
+            let k = BigUint::from_bytes_be(&bytes) % n;
+            prop_assert_eq!(g.mul_ct(&k, n).unwrap(), g.mul(k));
+        }
+    }
+}
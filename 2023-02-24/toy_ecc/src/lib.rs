@@ -1,18 +1,38 @@
 use std::fmt;
 use std::ops;
+use std::sync::Arc;
 
-use crate::element::Element;
+use crate::element::{Element, FieldElement};
+use crate::jacobian::JacobianPoint;
 use crate::util::buint;
 use num_bigint::BigUint;
 use num_bigint::ToBigInt;
 use num_bigint::ToBigUint;
 use thiserror::Error;
-use util::bint;
 
 // `Element` is meant to be part of this library's public interface,
 // but the util and eea modules aren't. They're internal helpers.
+pub mod curves;
+pub mod ecdh;
+pub mod ecdh_attacks;
+pub mod ecdlp;
+pub mod ecdsa;
+pub mod edwards;
 mod eea;
 pub mod element;
+pub mod elgamal;
+pub mod encoding;
+pub mod extension;
+pub mod hash_to_curve;
+pub mod isomorphism;
+pub mod jacobian;
+pub mod ladder;
+pub mod montgomery;
+pub mod msm;
+pub mod order;
+pub mod pairing;
+pub mod polynomial;
+pub mod schnorr;
 mod util;
 
 #[derive(Error, Debug)]
@@ -25,6 +45,58 @@ pub enum ECCError {
     NoModulusForPointAtInfinity,
     #[error("Cannot compute EEA because one or more operand is zero")]
     EEAOperandIsZero,
+    #[error("Scalar must be between 1 and the group order (excluded)")]
+    InvalidScalar,
+    #[error("Invalid signature")]
+    InvalidSignature,
+    #[error("Point at infinity is not a valid public key")]
+    PointAtInfinity,
+    #[error("Point is not on the curve")]
+    PointNotOnCurve,
+    #[error("Point is not in the prime-order subgroup")]
+    PointNotInSubgroup,
+    #[error("Attack failed to recover the secret")]
+    AttackFailed,
+    #[error("No discrete logarithm: Q is not a multiple of P")]
+    NoDiscreteLog,
+    #[error("Invalid point encoding")]
+    InvalidEncoding,
+    #[error("Cannot operate on elements with different moduli")]
+    ModulusMismatch,
+    #[error("Element is not invertible")]
+    NotInvertible,
+    #[error("Polynomial is not irreducible")]
+    NotIrreducible,
+    #[error("Point is not an n-torsion point")]
+    NotTorsionPoint,
+    #[error("The field doesn't contain the n-th roots of unity")]
+    MissingRootsOfUnity,
+    #[error("Cannot evaluate the Miller function of P at Q: Q is a multiple of P")]
+    PairingEvaluationFailed,
+    #[error("Unknown curve name")]
+    UnknownCurve,
+    #[error("Multi-scalar multiplication needs as many scalars as points")]
+    MsmLengthMismatch,
+    #[error("Multi-scalar multiplication needs at least one point")]
+    MsmEmptyInput,
+    #[error("wNAF width must be between 2 and 16")]
+    InvalidWindow,
+    #[error("Expected a curve in short Weierstrass form (a2 = 0)")]
+    NotShortWeierstrass,
+    #[error("Message is too large to be encoded as a point")]
+    MessageTooLarge,
+    #[error("Could not encode the message as a point")]
+    EncodingFailed,
+    #[error("expand_message_xmd cannot output that many bytes")]
+    InvalidExpandLength,
+    #[error("No valid Z parameter for the SSWU map on this curve")]
+    NoSswuParameter,
+    #[error("No suitable isogeny found for this curve")]
+    NoIsogeny,
+    #[error("Curve is singular: its discriminant is zero")]
+    SingularCurve,
+    #[error("No multiple of the order of the point in the Hasse interval")]
+    OrderNotFound,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -34,16 +106,40 @@ pub enum CurveForm {
     Unknown,
 }
 
+/// y^2 = x^3 + a2.x^2 + a4.x + a6, with coefficients in F_p by default.
+/// Curves over an extension field F_p^k use `ExtElement` coefficients instead.
+///
+/// Curves are cheap to clone: they share their parameters. Points hold a clone of their curve.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Curve<'a> {
-    name: &'a str,
+pub struct Curve<F = Element> {
+    params: Arc<CurveParams<F>>,
+}
+
+/// Everything that defines a curve. The generator, its order and the cofactor are optional:
+/// toy curves often don't come with any.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CurveParams<F = Element> {
+    name: String,
+    // Characteristic of the field
     p: BigUint,
-    a2: Element,
-    a4: Element,
-    a6: Element,
+    a2: F,
+    a4: F,
+    a6: F,
+    // Affine coordinates: a point can't live in the parameters of its own curve
+    generator: Option<(F, F)>,
+    order: Option<BigUint>,
+    cofactor: Option<BigUint>,
+}
+
+impl<F> ops::Deref for Curve<F> {
+    type Target = CurveParams<F>;
+
+    fn deref(&self) -> &CurveParams<F> {
+        &self.params
+    }
 }
 
-impl fmt::Display for Curve<'_> {
+impl fmt::Display for Curve {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "<Curve {}", self.name)?;
         match self.form() {
@@ -52,20 +148,20 @@ impl fmt::Display for Curve<'_> {
             CurveForm::Unknown => write!(f, ":")?,
         }
         write!(f, " y^2 = x^3")?;
-        if self.a2.value != buint(0) {
-            write!(f, " + {}x^2", self.a2.value)?;
+        if self.a2.value() != buint(0) {
+            write!(f, " + {}x^2", self.a2.value())?;
         }
-        if self.a4.value != buint(0) {
-            write!(f, " + {}x", self.a4.value)?;
+        if self.a4.value() != buint(0) {
+            write!(f, " + {}x", self.a4.value())?;
         }
-        if self.a6.value != buint(0) {
-            write!(f, " + {}", self.a6.value)?;
+        if self.a6.value() != buint(0) {
+            write!(f, " + {}", self.a6.value())?;
         }
         write!(f, " (mod {})>", self.p)
     }
 }
 
-impl Curve<'_> {
+impl Curve {
     pub fn new<BUINT: ToBigUint, BINT: ToBigInt>(
         name: &str,
         a2: BINT,
@@ -76,87 +172,229 @@ impl Curve<'_> {
         let prime = p.to_biguint().unwrap();
 
         Curve {
-            name,
-            p: prime.clone(),
-            a2: Element::new(a2, prime.clone()),
-            a4: Element::new(a4, prime.clone()),
-            a6: Element::new(a6, prime),
+            params: Arc::new(CurveParams {
+                name: name.to_string(),
+                p: prime.clone(),
+                a2: Element::new(a2, prime.clone()),
+                a4: Element::new(a4, prime.clone()),
+                a6: Element::new(a6, prime),
+                generator: None,
+                order: None,
+                cofactor: None,
+            }),
         }
     }
 
-    fn form(&self) -> CurveForm {
-        if self.a4 == self.zero() && self.a2 != self.zero() {
-            CurveForm::Montgomery
-        } else if self.a2 == self.zero() && self.a4 != self.zero() {
-            CurveForm::Weierstrass
-        } else {
-            // Curve isn't a Montgomery or Weierstrass curve
-            // ...but it's still a valid elliptic curve!
-            CurveForm::Unknown
+    /// Same as `new`, but errors with `SingularCurve` if the discriminant is zero.
+    pub fn checked_new<BUINT: ToBigUint, BINT: ToBigInt>(
+        name: &str,
+        a2: BINT,
+        a4: BINT,
+        a6: BINT,
+        p: BUINT,
+    ) -> Result<Curve, ECCError> {
+        let curve = Curve::new(name, a2, a4, a6, p);
+        if !curve.is_smooth() {
+            return Err(ECCError::SingularCurve);
+        }
+        Ok(curve)
+    }
+
+    /// The same curve, with a generator G of order n (prime, for real curves), and the cofactor
+    /// h = #E / n. Errors if G isn't on the curve, or if [n]G isn't O.
+    pub fn with_generator<T: ToBigInt>(
+        &self,
+        x: T,
+        y: T,
+        order: BigUint,
+        cofactor: BigUint,
+    ) -> Result<Curve, ECCError> {
+        let curve = self.with_generator_unchecked(x, y, order, cofactor);
+        let generator = curve.generator().unwrap();
+        if generator.is_at_infinity() || !curve.contains(generator.clone()) {
+            return Err(ECCError::PointNotOnCurve);
+        }
+        if !generator.mul(curve.order.clone().unwrap()).is_at_infinity() {
+            return Err(ECCError::PointNotInSubgroup);
+        }
+        Ok(curve)
+    }
+
+    pub(crate) fn with_generator_unchecked<T: ToBigInt>(
+        &self,
+        x: T,
+        y: T,
+        order: BigUint,
+        cofactor: BigUint,
+    ) -> Curve {
+        let generator = self.point(x, y);
+        Curve {
+            params: Arc::new(CurveParams {
+                name: self.name.clone(),
+                p: self.p.clone(),
+                a2: self.a2.clone(),
+                a4: self.a4.clone(),
+                a6: self.a6.clone(),
+                generator: Some((generator.x, generator.y)),
+                order: Some(order),
+                cofactor: Some(cofactor),
+            }),
         }
     }
 
     pub fn discriminant(&self) -> BigUint {
+        self.delta().value()
+    }
+
+    fn delta(&self) -> Element {
         // See https://mathworld.wolfram.com/EllipticDiscriminant.html for this formula
         let b2 = self.element(4) * self.a2.clone();
         let b4 = self.element(2) * self.a4.clone();
         let b6 = self.element(4) * self.a6.clone();
         let b8 = b2.clone() * self.a6.clone() - self.a4.pow(2);
 
-        let delta = -b8 * b2.pow(2) - self.element(8) * b4.pow(3) - self.element(27) * b6.pow(2)
-            + self.element(9) * b2 * b4 * b6;
-
-        delta.value
+        -b8 * b2.pow(2) - self.element(8) * b4.pow(3) - self.element(27) * b6.pow(2)
+            + self.element(9) * b2 * b4 * b6
     }
 
     pub fn is_smooth(&self) -> bool {
         self.discriminant() != buint(0)
     }
 
-    pub fn contains(&self, point: Point) -> bool {
+    /// j = c4^3 / Δ, with c4 = b2^2 - 24.b4 (same source as the discriminant). Two curves over
+    /// F_p are isomorphic over the algebraic closure iff they have the same j-invariant (p > 3).
+    /// Errors with `SingularCurve` if Δ = 0.
+    pub fn j_invariant(&self) -> Result<Element, ECCError> {
+        let delta = self.delta();
+        if delta.is_zero() {
+            return Err(ECCError::SingularCurve);
+        }
+        let b2 = self.element(4) * self.a2.clone();
+        let b4 = self.element(2) * self.a4.clone();
+        let c4 = b2.pow(2) - self.element(24) * b4;
+        c4.pow(3).checked_div(&delta)
+    }
+
+    pub fn point<T: ToBigInt>(&self, x: T, y: T) -> Point {
+        Point {
+            x: Element::in_field(x, self.a2.field()),
+            y: Element::in_field(y, self.a2.field()),
+            infinity: false,
+            curve: self.clone(),
+        }
+    }
+}
+
+impl<F: FieldElement> Curve<F> {
+    /// A curve with coefficients in any field, e.g. F_p^k. All three must be in the same field.
+    pub fn from_elements(name: &str, a2: F, a4: F, a6: F) -> Result<Self, ECCError> {
+        a2.checked_add(&a4)?.checked_add(&a6)?;
+        Ok(Curve {
+            params: Arc::new(CurveParams {
+                name: name.to_string(),
+                p: a2.characteristic().clone(),
+                a2,
+                a4,
+                a6,
+                generator: None,
+                order: None,
+                cofactor: None,
+            }),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The generator given by `with_generator`, if any
+    pub fn generator(&self) -> Option<Point<F>> {
+        let (x, y) = self.generator.clone()?;
+        Some(Point {
+            x,
+            y,
+            infinity: false,
+            curve: self.clone(),
+        })
+    }
+
+    /// Order of the generator, if any
+    pub fn subgroup_order(&self) -> Option<&BigUint> {
+        self.order.as_ref()
+    }
+
+    /// #E / n, with n the order of the generator, if any
+    pub fn cofactor(&self) -> Option<&BigUint> {
+        self.cofactor.as_ref()
+    }
+
+    fn form(&self) -> CurveForm {
+        if self.a4 == self.zero() && self.a2 != self.zero() {
+            CurveForm::Montgomery
+        } else if self.a2 == self.zero() && self.a4 != self.zero() {
+            CurveForm::Weierstrass
+        } else {
+            // Curve isn't a Montgomery or Weierstrass curve
+            // ...but it's still a valid elliptic curve!
+            CurveForm::Unknown
+        }
+    }
+
+    pub fn contains(&self, point: Point<F>) -> bool {
         if point.is_at_infinity() {
             true
         } else {
-            let x = point.x;
-            let y = point.y;
-            y.pow(2)
-                == (x.pow(3) + self.a2.clone() * x.pow(2) + self.a4.clone() * x + self.a6.clone())
+            point.y.pow(2) == self.rhs(&point.x)
         }
     }
 
-    pub fn element<T: ToBigInt>(&self, value: T) -> Element {
-        Element::new(value, self.p.clone())
+    /// x^3 + a2.x^2 + a4.x + a6
+    pub(crate) fn rhs(&self, x: &F) -> F {
+        x.pow(3) + self.a2.clone() * x.pow(2) + self.a4.clone() * x.clone() + self.a6.clone()
     }
 
-    pub fn point<T: ToBigInt>(&self, x: T, y: T) -> Point {
-        Point {
-            x: Element::new(bint(x), self.p.clone()),
-            y: Element::new(bint(y), self.p.clone()),
-            curve: self,
+    pub fn element<T: ToBigInt>(&self, value: T) -> F {
+        self.a2.embed(value)
+    }
+
+    /// Same as `point`, for coordinates which are already field elements.
+    /// Like `point`, this doesn't check that the point is on the curve.
+    pub fn point_from_elements(&self, x: F, y: F) -> Result<Point<F>, ECCError> {
+        if x.checked_add(&self.a2).is_err() || y.checked_add(&self.a2).is_err() {
+            return Err(ECCError::PointCoordinateFieldMismatch);
         }
+        Ok(Point {
+            x,
+            y,
+            infinity: false,
+            curve: self.clone(),
+        })
     }
 
-    pub fn point_at_infinity(&self) -> Point {
+    pub fn point_at_infinity(&self) -> Point<F> {
         Point {
-            x: Element::new(0, self.p.clone()),
-            y: Element::new(0, self.p.clone()),
-            curve: self,
+            x: self.zero(),
+            y: self.zero(),
+            infinity: true,
+            curve: self.clone(),
         }
     }
 
-    pub fn zero(&self) -> Element {
-        Element::new(0, self.p.clone())
+    pub fn zero(&self) -> F {
+        self.a2.embed(0)
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Point<'p> {
-    x: Element,
-    y: Element,
-    curve: &'p Curve<'p>,
+pub struct Point<F = Element> {
+    x: F,
+    y: F,
+    // O has no affine coordinates: x and y are left at 0, which is a regular point when a6 = 0
+    infinity: bool,
+    curve: Curve<F>,
 }
 
-impl fmt::Display for Point<'_> {
+impl fmt::Display for Point {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.is_at_infinity() {
             write!(f, "<Point at Infinity>")
@@ -166,13 +404,15 @@ impl fmt::Display for Point<'_> {
             write!(
                 f,
                 "<Point on curve {}: x={}, y={}>",
-                self.curve.name, x.value, y.value
+                self.curve.name,
+                x.value(),
+                y.value()
             )
         }
     }
 }
 
-impl ops::Neg for Point<'_> {
+impl<F: FieldElement> ops::Neg for Point<F> {
     type Output = Self;
     fn neg(self) -> Self::Output {
         if self.is_at_infinity() {
@@ -181,87 +421,124 @@ impl ops::Neg for Point<'_> {
             Self {
                 x: self.x,
                 y: -self.y,
-                curve: self.curve,
+                infinity: false,
+                curve: self.curve.clone(),
             }
         }
     }
 }
 
-impl ops::Add for Point<'_> {
+impl<F: FieldElement> ops::Add for Point<F> {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
+        self.checked_add(&other).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl Point {
+    pub fn modulus(&self) -> Result<BigUint, ECCError> {
+        if self.is_at_infinity() {
+            return Err(ECCError::NoModulusForPointAtInfinity);
+        }
+        let x = self.x.clone();
+        let y = self.y.clone();
+
+        if x.modulus() != y.modulus() {
+            return Err(ECCError::PointCoordinateFieldMismatch);
+        }
+        Ok(x.modulus().clone())
+    }
+}
+
+impl<F: FieldElement> Point<F> {
+    /// Same as `+`, but returns an error instead of panicking when the points are on different curves,
+    /// or when the points aren't on the curve and the addition formulas divide by zero.
+    pub fn checked_add(&self, other: &Self) -> Result<Self, ECCError> {
         if other.curve != self.curve {
-            panic!("{}", ECCError::CurveMismatch)
+            return Err(ECCError::CurveMismatch);
         }
 
         // O + X = X
         if self.is_at_infinity() && !other.is_at_infinity() {
-            return other;
+            return Ok(other.clone());
         }
         // X + O = X
         if !self.is_at_infinity() && other.is_at_infinity() {
-            return self;
+            return Ok(self.clone());
         }
         // O + O = O
         if self.is_at_infinity() && other.is_at_infinity() {
-            return self.curve.point_at_infinity();
+            return Ok(self.curve.point_at_infinity());
         }
 
         // We're now in the regular case: two normal points!
-        let x = self.x.clone();
-        let y = self.y.clone();
-        let other_x = other.x.clone();
-        let other_y = other.y.clone();
-        let modulus = self.modulus().unwrap();
+        let x = &self.x;
+        let y = &self.y;
+        let other_x = &other.x;
+        let other_y = &other.y;
+        if x.checked_add(y).is_err() || other_x.checked_add(other_y).is_err() {
+            return Err(ECCError::PointCoordinateFieldMismatch);
+        }
 
         // P + -P = O
-        if x == other_x && (y.clone() + other_y.clone()).is_zero() {
-            return self.curve.point_at_infinity();
+        if x == other_x && y.checked_add(other_y)?.is_zero() {
+            return Ok(self.curve.point_at_infinity());
         }
 
+        // The formulas below are the general ones for y^2 = x^3 + a2.x^2 + a4.x + a6.
+        // When a2 = 0 they reduce to the usual short Weierstrass formulas.
+        let a2 = &self.curve.a2;
         let lambda = if self == other {
-            // Compute Lambda for doubling
-            (self.curve.element(3) * x.pow(2) + self.curve.clone().a4)
-                / (Element::new(2, modulus) * y.clone())
+            // Compute Lambda for doubling: (3x^2 + 2.a2.x + a4) / 2y
+            let numerator = self
+                .curve
+                .element(3)
+                .checked_mul(&x.pow(2))?
+                .checked_add(&self.curve.element(2).checked_mul(a2)?.checked_mul(x)?)?
+                .checked_add(&self.curve.a4)?;
+            numerator.checked_div(&self.curve.element(2).checked_mul(y)?)?
         } else {
             // Standard formula, when the two points are distinct
-            (other_y - y.clone()) / (other_x.clone() - x.clone())
+            other_y
+                .checked_sub(y)?
+                .checked_div(&other_x.checked_sub(x)?)?
         };
 
-        let result_x = lambda.pow(2) - x.clone() - other_x;
-        let result_y = lambda * (x - result_x.clone()) - y;
+        let result_x = lambda
+            .pow(2)
+            .checked_sub(a2)?
+            .checked_sub(x)?
+            .checked_sub(other_x)?;
+        let result_y = lambda
+            .checked_mul(&x.checked_sub(&result_x)?)?
+            .checked_sub(y)?;
 
         // X + Y (both regular points)
-        Point {
+        Ok(Point {
             x: result_x,
             y: result_y,
-            curve: self.curve,
-        }
+            infinity: false,
+            curve: self.curve.clone(),
+        })
     }
-}
 
-impl Point<'_> {
     pub fn is_at_infinity(&self) -> bool {
-        self.x.is_zero() && self.y.is_zero()
+        self.infinity
     }
 
-    pub fn modulus(&self) -> Result<BigUint, ECCError> {
-        if self.is_at_infinity() {
-            return Err(ECCError::NoModulusForPointAtInfinity);
-        }
-        let x = self.x.clone();
-        let y = self.y.clone();
-
-        if x.modulus != y.modulus {
-            return Err(ECCError::PointCoordinateFieldMismatch);
-        }
-        Ok(x.modulus)
+    // Point multiplication, implemented with the double-and-add technique
+    // (deviates from the OG toy_ecc implementation, which did this with non-adjacent representation:
+    // see `mul_naf` and `mul_wnaf` in the msm module for that)
+    // Intermediate points are kept in Jacobian coordinates, so we only pay for one field inversion
+    // at the very end, when converting back to affine coordinates.
+    pub fn mul(&self, scalar: BigUint) -> Point<F> {
+        JacobianPoint::from(self.clone()).mul(scalar).to_affine()
     }
 
-    // Point multiplication, implemented with the double-and-add technique
-    // (deviates from the OG toy_ecc implementation, which did this with non-adjacent representation)
-    pub fn mul(&self, scalar: BigUint) -> Point {
+    // Same as `mul`, but every intermediate addition happens in affine coordinates.
+    // Each addition costs a field inversion: this is much slower, and kept around as a reference.
+    pub fn mul_affine(&self, scalar: BigUint) -> Point<F> {
         if scalar == buint(0) {
             return self.curve.point_at_infinity();
         }
@@ -286,22 +563,125 @@ impl Point<'_> {
 #[cfg(test)]
 mod test {
 
-    use crate::util::{bint, buint};
-    use crate::Curve;
+    use crate::element::Element;
+    use crate::util::buint;
+    use crate::{Curve, CurveForm, ECCError, Point};
     use num_bigint::BigUint;
+    use proptest::prelude::*;
 
-    fn curve_secp256k1() -> Curve<'static> {
-        let p_secp256 = bint(2).pow(256) - bint(2).pow(32) - bint(977);
-        Curve::new("secp256k1", 0, 0, 7, p_secp256)
+    fn curve_secp256k1() -> Curve {
+        Curve::named("secp256k1").unwrap()
     }
 
     // Curve from Introduction to Mathematical Cryptography
     // Chapter 5, section 2 and 3
     // Y^2 =X^3+3X+8 on F13
-    fn curve_trivial() -> Curve<'static> {
+    fn curve_trivial() -> Curve {
         Curve::new("trivial curve", 0, 3, 8, 13)
     }
 
+    // Brute-force enumeration of all the points on a (tiny) curve, including the point at infinity
+    fn all_points(curve: &Curve, p: u32) -> Vec<Point> {
+        let mut points = vec![curve.point_at_infinity()];
+        for x in 0..p {
+            for y in 0..p {
+                let point = curve.point(x, y);
+                if curve.contains(point.clone()) {
+                    points.push(point);
+                }
+            }
+        }
+        points
+    }
+
+    // Checks the group law on every point (closure, identity, inverse, commutativity)
+    // and every triple of points (associativity).
+    fn check_group_law(curve: &Curve, p: u32) {
+        assert!(curve.is_smooth());
+        let points = all_points(curve, p);
+        let inf = curve.point_at_infinity();
+
+        for a in &points {
+            assert_eq!(a.clone() + inf.clone(), a.clone());
+            assert!((a.clone() + -a.clone()).is_at_infinity());
+            for b in &points {
+                let sum = a.clone() + b.clone();
+                assert!(
+                    curve.contains(sum.clone()),
+                    "{a} + {b} = {sum} is off-curve"
+                );
+                assert_eq!(sum, b.clone() + a.clone());
+                for c in &points {
+                    assert_eq!(
+                        (a.clone() + b.clone()) + c.clone(),
+                        a.clone() + (b.clone() + c.clone()),
+                        "associativity fails for {a}, {b}, {c}"
+                    );
+                }
+            }
+        }
+
+        // Lagrange: the order of the group kills every point
+        let order = buint(points.len());
+        for a in &points {
+            assert!(a.mul(order.clone()).is_at_infinity());
+        }
+    }
+
+    #[test]
+    fn test_group_law_weierstrass() {
+        let c = curve_trivial();
+        assert_eq!(c.form(), CurveForm::Weierstrass);
+        check_group_law(&c, 13);
+    }
+
+    #[test]
+    fn test_group_law_montgomery() {
+        // y^2 = x^3 + 5x^2 + 3 on F17
+        let c = Curve::new("montgomery", 5, 0, 3, 17);
+        assert_eq!(c.form(), CurveForm::Montgomery);
+        check_group_law(&c, 17);
+    }
+
+    #[test]
+    fn test_group_law_unknown_form() {
+        // y^2 = x^3 + 2x^2 + 3x + 5 on F19
+        let c = Curve::new("unknown", 2, 3, 5, 19);
+        assert_eq!(c.form(), CurveForm::Unknown);
+        check_group_law(&c, 19);
+    }
+
+    #[test]
+    fn test_group_law_two_torsion_at_origin() {
+        // y^2 = x^3 + x on F13: with a6 = 0, (0, 0) is a point of order 2, not O
+        let c = Curve::new("a6 = 0", 0, 1, 0, 13);
+        let t = c.point(0, 0);
+        assert!(c.contains(t.clone()));
+        assert!(!t.is_at_infinity());
+        assert!((t.clone() + t.clone()).is_at_infinity());
+        assert!(t.mul(buint(2)).is_at_infinity());
+        assert_eq!(t.mul(buint(3)), t);
+
+        let p = c.point(2, 6);
+        assert!(c.contains(p.clone()));
+        assert_ne!(p.clone() + t.clone(), p);
+        check_group_law(&c, 13);
+    }
+
+    #[test]
+    fn test_point_doubling_with_a2() {
+        // y^2 = x^3 + 5x^2 + 3 on F17. P = (1, 3):
+        // lambda = (3 + 10) / 6 = 13 * 3 = 5 (mod 17)
+        // x = 25 - 5 - 2 = 18 = 1 (mod 17), y = 5 * (1 - 1) - 3 = 14 (mod 17)
+        let c = Curve::new("montgomery", 5, 0, 3, 17);
+        let p = c.point(1, 3);
+        assert!(c.contains(p.clone()));
+
+        let double = p.clone() + p;
+        assert_eq!(double.x.value(), buint(1));
+        assert_eq!(double.y.value(), buint(14));
+    }
+
     #[test]
     fn test_curve_display() {
         let montgomery = Curve::new("MyCurve", 44, 0, 46, 89);
@@ -362,6 +742,40 @@ mod test {
         assert_eq!(secp256k1_discriminant, secp.discriminant());
     }
 
+    #[test]
+    fn test_j_invariant() {
+        // j = 1728.4a^3 / (4a^3 + 27b^2) for short Weierstrass curves
+        assert_eq!(curve_trivial().j_invariant().unwrap().value(), buint(3));
+        let itmc_631 = Curve::named("itmc-631").unwrap();
+        assert_eq!(itmc_631.j_invariant().unwrap().value(), buint(269));
+        assert_eq!(curve_secp256k1().j_invariant().unwrap().value(), buint(0));
+        assert_eq!(
+            Curve::new("y^2 = x^3 + x", 0, 1, 0, 13)
+                .j_invariant()
+                .unwrap(),
+            Element::new(1728, 13)
+        );
+
+        assert!(matches!(
+            Curve::new("Cusp", 0, 0, 0, 13).j_invariant(),
+            Err(ECCError::SingularCurve)
+        ));
+    }
+
+    #[test]
+    fn test_checked_new() {
+        assert!(Curve::checked_new("trivial curve", 0, 3, 8, 13).is_ok());
+        assert!(matches!(
+            Curve::checked_new("Node", 0, -3, 2, 13),
+            Err(ECCError::SingularCurve)
+        ));
+        // y^2 = x^2.(x + 1) has a node at (0, 0)
+        assert!(matches!(
+            Curve::checked_new("Montgomery node", 1, 0, 0, 13),
+            Err(ECCError::SingularCurve)
+        ));
+    }
+
     #[test]
     fn test_negate_point() {
         let curve = Curve::new("My curve", 7, 0, 1, 17);
@@ -369,8 +783,8 @@ mod test {
 
         let minus_p = -p;
 
-        assert_eq!(minus_p.x.value, buint(1));
-        assert_eq!(minus_p.y.value, buint(15));
+        assert_eq!(minus_p.x.value(), buint(1));
+        assert_eq!(minus_p.y.value(), buint(15));
     }
 
     #[test]
@@ -400,12 +814,12 @@ mod test {
         let q = c.point(9, 6);
 
         let sum = p.clone() + q;
-        assert_eq!(sum.x.value, buint(12));
-        assert_eq!(sum.y.value, buint(2));
+        assert_eq!(sum.x.value(), buint(12));
+        assert_eq!(sum.y.value(), buint(2));
 
         let double = p.clone() + p;
-        assert_eq!(double.x.value, buint(12));
-        assert_eq!(double.y.value, buint(11));
+        assert_eq!(double.x.value(), buint(12));
+        assert_eq!(double.y.value(), buint(11));
     }
 
     #[test]
@@ -481,8 +895,8 @@ mod test {
         // Hence proving the correctness of these expected values: we just showed that they yield the right BTC address!
         // --------------------------------------------------------------------------------------------------------------------------------------------------
 
-        assert_eq!(sum.clone().x.value, expected_x);
-        assert_eq!(sum.clone().y.value, expected_y);
+        assert_eq!(sum.clone().x.value(), expected_x);
+        assert_eq!(sum.clone().y.value(), expected_y);
         assert_eq!(sum.modulus().unwrap(), curve_secp256k1().p);
     }
 
@@ -490,7 +904,7 @@ mod test {
     fn test_addition_with_infinity_point() {
         let c = curve_secp256k1();
         let p = c.point(1, 2);
-        let inf = c.point(0, 0);
+        let inf = c.point_at_infinity();
 
         assert_eq!(p.clone() + inf.clone(), p.clone());
         assert_eq!(inf.clone() + p.clone(), p.clone());
@@ -508,20 +922,20 @@ mod test {
         assert_eq!(noop_mul.y, p.y);
 
         let double = p.mul(buint(2));
-        assert_eq!(double.x.value, buint(2));
-        assert_eq!(double.y.value, buint(3));
+        assert_eq!(double.x.value(), buint(2));
+        assert_eq!(double.y.value(), buint(3));
 
         let triple = p.mul(buint(3));
-        assert_eq!(triple.x.value, buint(9));
-        assert_eq!(triple.y.value, buint(6));
+        assert_eq!(triple.x.value(), buint(9));
+        assert_eq!(triple.y.value(), buint(6));
 
         let quadruple = p.mul(buint(4));
-        assert_eq!(quadruple.x.value, buint(12));
-        assert_eq!(quadruple.y.value, buint(11));
+        assert_eq!(quadruple.x.value(), buint(12));
+        assert_eq!(quadruple.y.value(), buint(11));
 
         let quintuple = p.mul(buint(5));
-        assert_eq!(quintuple.x.value, buint(12));
-        assert_eq!(quintuple.y.value, buint(2));
+        assert_eq!(quintuple.x.value(), buint(12));
+        assert_eq!(quintuple.y.value(), buint(2));
 
         let times_nine = p.mul(buint(9));
         assert_eq!(times_nine, c.point_at_infinity());
@@ -530,4 +944,66 @@ mod test {
         let times_thousand = p.mul(buint(9000));
         assert_eq!(times_thousand, c.point_at_infinity());
     }
+
+    #[test]
+    fn test_with_generator() {
+        let c = curve_trivial();
+        let with_g = c.with_generator(1, 8, buint(9), buint(1)).unwrap();
+        assert_eq!(with_g.generator(), Some(with_g.point(1, 8)));
+        assert_eq!(with_g.subgroup_order(), Some(&buint(9)));
+        assert!(c.generator().is_none());
+        assert_ne!(c, with_g);
+        assert!(matches!(
+            c.with_generator(1, 3, buint(9), buint(1)),
+            Err(ECCError::PointNotOnCurve)
+        ));
+        assert!(matches!(
+            c.with_generator(1, 8, buint(3), buint(3)),
+            Err(ECCError::PointNotInSubgroup)
+        ));
+    }
+
+    #[test]
+    fn test_checked_add() {
+        let c = Curve::new("c", 0, 3, 8, 13);
+        let other = Curve::new("other", 0, 3, 8, 17);
+        let p = c.point(1, 8);
+        assert_eq!(p.checked_add(&p).unwrap(), p.clone() + p.clone());
+        assert!(matches!(
+            p.checked_add(&other.point(1, 8)),
+            Err(ECCError::CurveMismatch)
+        ));
+        // Off-curve points with the same x: the chord is vertical, but they aren't opposite
+        assert!(matches!(
+            p.checked_add(&c.point(1, 3)),
+            Err(ECCError::NotInvertible)
+        ));
+    }
+
+    proptest! {
+        // Adversarial coordinates (on the curve or not, on the same curve or not):
+        // checked_add never panics, and agrees with `+` whenever it succeeds.
+        #[test]
+        fn test_fuzz_checked_add(
+            x1 in 0u64..13,
+            y1 in 0u64..13,
+            x2 in 0u64..13,
+            y2 in 0u64..13,
+            same_curve in any::<bool>(),
+        ) {
+            let c = Curve::new("c", 0, 3, 8, 13);
+            let other = Curve::new("other", 2, 3, 8, 13);
+            let p = c.point(x1, y1);
+            let q = if same_curve { c.point(x2, y2) } else { other.point(x2, y2) };
+
+            match p.checked_add(&q) {
+                Ok(sum) => prop_assert_eq!(sum, p + q),
+                Err(ECCError::CurveMismatch) => prop_assert!(!same_curve),
+                Err(ECCError::NotInvertible) => {
+                    prop_assert!(!c.contains(p.clone()) || !c.contains(q.clone()))
+                }
+                Err(e) => prop_assert!(false, "unexpected error: {}", e),
+            }
+        }
+    }
 }
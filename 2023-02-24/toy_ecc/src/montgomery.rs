@@ -0,0 +1,389 @@
+use std::fmt;
+
+use num_bigint::{BigUint, ToBigInt, ToBigUint};
+
+use crate::edwards::{EdwardsCurve, EdwardsPoint};
+use crate::element::Element;
+use crate::util::{bint, buint};
+use crate::{Curve, Point};
+
+/// Montgomery curve: B.v^2 = u^3 + A.u^2 + u (mod p)
+///
+/// Montgomery curves shine when we only care about u-coordinates: the Montgomery ladder computes
+/// u([k]P) from u(P) alone, with the same sequence of operations for every bit of k.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MontgomeryCurve {
+    name: String,
+    p: BigUint,
+    a: Element,
+    b: Element,
+}
+
+/// Affine point (u, v) on a `MontgomeryCurve`.
+/// This is only used to move points across curve models: arithmetic on Montgomery curves is x-only.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MontgomeryPoint {
+    u: Element,
+    v: Element,
+}
+
+impl MontgomeryPoint {
+    pub fn new(u: Element, v: Element) -> Self {
+        Self { u, v }
+    }
+
+    pub fn u(&self) -> &Element {
+        &self.u
+    }
+
+    pub fn v(&self) -> &Element {
+        &self.v
+    }
+}
+
+impl fmt::Display for MontgomeryCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<Curve {} (Montgomery): {}v^2 = u^3 + {}u^2 + u (mod {})>",
+            self.name,
+            self.b.value(),
+            self.a.value(),
+            self.p
+        )
+    }
+}
+
+impl MontgomeryCurve {
+    pub fn new<BUINT: ToBigUint, BINT: ToBigInt>(
+        name: &str,
+        a: BINT,
+        b: BINT,
+        p: BUINT,
+    ) -> MontgomeryCurve {
+        let prime = buint(p);
+        MontgomeryCurve {
+            name: name.to_string(),
+            p: prime.clone(),
+            a: Element::new(a, prime.clone()),
+            b: Element::new(b, prime),
+        }
+    }
+
+    /// Same as `new`, for when `A` and `B` are already field elements.
+    pub fn from_elements(name: &str, a: Element, b: Element) -> MontgomeryCurve {
+        MontgomeryCurve {
+            name: name.to_string(),
+            p: a.modulus().clone(),
+            a,
+            b,
+        }
+    }
+
+    pub fn a(&self) -> &Element {
+        &self.a
+    }
+
+    pub fn b(&self) -> &Element {
+        &self.b
+    }
+
+    pub fn contains(&self, point: &MontgomeryPoint) -> bool {
+        let u = point.u.clone();
+        self.b.clone() * point.v.pow(2) == u.pow(3) + self.a.clone() * u.pow(2) + u
+    }
+
+    pub fn element<T: ToBigInt>(&self, value: T) -> Element {
+        Element::in_field(value, self.a.field())
+    }
+
+    /// x-only Montgomery ladder: computes u([k]P) given u(P).
+    /// Returns 0 when [k]P is the point at infinity (like X25519 does).
+    ///
+    /// Each step uses the differential addition and doubling formulas with a24 = (A + 2) / 4.
+    /// The ladder does the same field operations for every bit of the scalar, but the swaps below
+    /// are plain branches on BigUint values: this isn't constant-time!
+    pub fn ladder(&self, scalar: &BigUint, u: &Element) -> Element {
+        let a24 = (self.a.clone() + self.element(2)) / self.element(4);
+        let x1 = u.clone();
+        let (mut x2, mut z2) = (self.element(1), self.element(0));
+        let (mut x3, mut z3) = (u.clone(), self.element(1));
+
+        let mut swap = false;
+        for i in (0..scalar.bits()).rev() {
+            let bit = scalar.bit(i);
+            if swap ^ bit {
+                std::mem::swap(&mut x2, &mut x3);
+                std::mem::swap(&mut z2, &mut z3);
+            }
+            swap = bit;
+
+            let a = x2.clone() + z2.clone();
+            let aa = a.pow(2);
+            let b = x2 - z2;
+            let bb = b.pow(2);
+            let e = aa.clone() - bb.clone();
+            let c = x3.clone() + z3.clone();
+            let d = x3 - z3;
+            let da = d * a;
+            let cb = c * b;
+
+            x3 = (da.clone() + cb.clone()).pow(2);
+            z3 = x1.clone() * (da - cb).pow(2);
+            x2 = aa * bb.clone();
+            z2 = e.clone() * (bb + a24.clone() * e);
+        }
+        if swap {
+            std::mem::swap(&mut x2, &mut x3);
+            std::mem::swap(&mut z2, &mut z3);
+        }
+
+        if z2.is_zero() {
+            self.element(0)
+        } else {
+            x2 / z2
+        }
+    }
+
+    /// The birationally equivalent twisted Edwards curve a.x^2 + y^2 = 1 + d.x^2.y^2, with
+    ///     a = (A + 2) / B
+    ///     d = (A - 2) / B
+    pub fn to_edwards(&self) -> EdwardsCurve {
+        EdwardsCurve::from_elements(
+            &self.name,
+            (self.a.clone() + self.element(2)) / self.b.clone(),
+            (self.a.clone() - self.element(2)) / self.b.clone(),
+        )
+    }
+
+    /// Maps a point to the twisted Edwards curve given by `to_edwards`:
+    ///     x = u / v
+    ///     y = (u - 1) / (u + 1)
+    /// (0, 0) maps to (0, -1). Points with v = 0 (other than (0, 0)) or u = -1 are exceptional, and yield `None`.
+    pub fn to_edwards_point(
+        &self,
+        curve: &EdwardsCurve,
+        point: &MontgomeryPoint,
+    ) -> Option<EdwardsPoint> {
+        if point.u.is_zero() && point.v.is_zero() {
+            return Some(curve.point(0, -1));
+        }
+        let u_plus_one = point.u.clone() + self.element(1);
+        if point.v.is_zero() || u_plus_one.is_zero() {
+            return None;
+        }
+        let x = point.u.clone() / point.v.clone();
+        let y = (point.u.clone() - self.element(1)) / u_plus_one;
+        Some(curve.point(bint(x.value()), bint(y.value())))
+    }
+
+    /// The isomorphic short Weierstrass curve y^2 = x^3 + a4.x + a6, with
+    ///     a4 = (3 - A^2) / 3B^2
+    ///     a6 = (2A^3 - 9A) / 27B^3
+    pub fn to_weierstrass(&self, name: &str) -> Curve {
+        let (a, b) = (self.a.clone(), self.b.clone());
+        let a4 = (self.element(3) - a.pow(2)) / (self.element(3) * b.pow(2));
+        let a6 = (self.element(2) * a.pow(3) - self.element(9) * a) / (self.element(27) * b.pow(3));
+        Curve::new(
+            name,
+            bint(0),
+            bint(a4.value()),
+            bint(a6.value()),
+            self.p.clone(),
+        )
+    }
+
+    /// Maps a point to the curve given by `to_weierstrass`:
+    ///     x = u/B + A/3B
+    ///     y = v/B
+    pub fn to_weierstrass_point(&self, curve: &Curve, point: &MontgomeryPoint) -> Point {
+        let x =
+            point.u.clone() / self.b.clone() + self.a.clone() / (self.element(3) * self.b.clone());
+        let y = point.v.clone() / self.b.clone();
+        curve.point(bint(x.value()), bint(y.value()))
+    }
+
+    /// Inverse of `to_weierstrass_point`:
+    ///     u = B.x - A/3
+    ///     v = B.y
+    /// The point at infinity has no affine counterpart, and yields `None`.
+    pub fn from_weierstrass_point(&self, point: &Point) -> Option<MontgomeryPoint> {
+        if point.is_at_infinity() {
+            return None;
+        }
+        Some(MontgomeryPoint {
+            u: self.b.clone() * point.x.clone() - self.a.clone() / self.element(3),
+            v: self.b.clone() * point.y.clone(),
+        })
+    }
+}
+
+/// X25519 function from RFC 7748: scalar multiplication on Curve25519 (A = 486662, p = 2^255 - 19),
+/// with the scalar and u-coordinate encoded as 32 little-endian bytes.
+pub fn x25519(scalar: [u8; 32], u: [u8; 32]) -> [u8; 32] {
+    // Clamp the scalar: clear the 3 lowest bits (cofactor is 8), clear bit 255 and set bit 254
+    let mut k = scalar;
+    k[0] &= 248;
+    k[31] &= 127;
+    k[31] |= 64;
+
+    // The most significant bit of u must be ignored
+    let mut u = u;
+    u[31] &= 127;
+
+    let p = buint(bint(2).pow(255) - bint(19));
+    let curve = MontgomeryCurve::new("Curve25519", 486662, 1, p.clone());
+    let result = curve.ladder(
+        &BigUint::from_bytes_le(&k),
+        &Element::new(BigUint::from_bytes_le(&u), p),
+    );
+
+    let mut out = [0u8; 32];
+    let bytes = result.value().to_bytes_le();
+    out[..bytes.len()].copy_from_slice(&bytes);
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+
+    use crate::montgomery::{x25519, MontgomeryCurve, MontgomeryPoint};
+    use crate::util::{bint, buint};
+
+    fn bytes(hex: &str) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn test_montgomery_display() {
+        let c = MontgomeryCurve::new("toy", 3, 1, 13);
+        assert_eq!(
+            format!("{c}"),
+            "<Curve toy (Montgomery): 1v^2 = u^3 + 3u^2 + u (mod 13)>"
+        );
+    }
+
+    #[test]
+    fn test_x25519_rfc7748_vectors() {
+        // RFC 7748, section 5.2
+        assert_eq!(
+            x25519(
+                bytes("a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4"),
+                bytes("e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c")
+            ),
+            bytes("c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552")
+        );
+        assert_eq!(
+            x25519(
+                bytes("4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d"),
+                bytes("e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493")
+            ),
+            bytes("95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957")
+        );
+
+        // First iteration of the iterated test: k = u = 9
+        let nine = bytes("0900000000000000000000000000000000000000000000000000000000000000");
+        assert_eq!(
+            x25519(nine, nine),
+            bytes("422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079")
+        );
+    }
+
+    #[test]
+    fn test_x25519_diffie_hellman() {
+        // RFC 7748, section 6.1
+        let nine = bytes("0900000000000000000000000000000000000000000000000000000000000000");
+        let alice_sk = bytes("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let bob_sk = bytes("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+
+        let alice_pk = x25519(alice_sk, nine);
+        let bob_pk = x25519(bob_sk, nine);
+        assert_eq!(
+            alice_pk,
+            bytes("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a")
+        );
+        assert_eq!(
+            bob_pk,
+            bytes("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")
+        );
+
+        let shared = bytes("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+        assert_eq!(x25519(alice_sk, bob_pk), shared);
+        assert_eq!(x25519(bob_sk, alice_pk), shared);
+    }
+
+    // Points on the toy curve v^2 = u^3 + 3u^2 + u on F13
+    fn toy_points(c: &MontgomeryCurve) -> Vec<MontgomeryPoint> {
+        let mut points = vec![];
+        for u in 0..13 {
+            for v in 0..13 {
+                let point = MontgomeryPoint::new(c.element(u), c.element(v));
+                if c.contains(&point) {
+                    points.push(point);
+                }
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn test_ladder_matches_weierstrass_arithmetic() {
+        let c = MontgomeryCurve::new("toy", 3, 1, 13);
+        let w = c.to_weierstrass("toy (Weierstrass)");
+        assert!(w.is_smooth());
+
+        for point in toy_points(&c) {
+            let mapped = c.to_weierstrass_point(&w, &point);
+            assert!(w.contains(mapped.clone()));
+            assert_eq!(c.from_weierstrass_point(&mapped), Some(point.clone()));
+
+            for k in 0..20u32 {
+                let expected = match c.from_weierstrass_point(&mapped.mul(buint(k))) {
+                    Some(q) => q.u,
+                    None => c.element(0),
+                };
+                assert_eq!(c.ladder(&buint(k), &point.u), expected, "k = {k}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_edwards_roundtrip_on_toy_curve() {
+        let c = MontgomeryCurve::new("toy", 3, 1, 13);
+        let e = c.to_edwards();
+        for point in toy_points(&c) {
+            if let Some(mapped) = c.to_edwards_point(&e, &point) {
+                assert!(e.contains(&mapped));
+                assert_eq!(mapped.to_montgomery(), Some(point));
+            }
+        }
+    }
+
+    #[test]
+    fn test_curve25519_to_weierstrass() {
+        let p = buint(bint(2).pow(255) - bint(19));
+        let c = MontgomeryCurve::new("Curve25519", 486662, 1, p);
+        let w = c.to_weierstrass("Wei25519");
+        assert!(w.is_smooth());
+
+        // Curve25519 base point: u = 9, and v from RFC 7748 section 4.1
+        let v = BigUint::parse_bytes(
+            b"14781619447589544791020593568409986887264606134616475288964881837755586237401",
+            10,
+        )
+        .unwrap();
+        let g = MontgomeryPoint::new(c.element(9), c.element(v));
+        assert!(c.contains(&g));
+        let mapped = c.to_weierstrass_point(&w, &g);
+        assert!(w.contains(mapped.clone()));
+
+        let k = buint(0xc0ffeeu32);
+        let expected = c.from_weierstrass_point(&mapped.mul(k.clone())).unwrap();
+        assert_eq!(c.ladder(&k, &g.u), expected.u);
+    }
+}
@@ -0,0 +1,352 @@
+//! Faster scalar multiplications, for one point or for many.
+//!
+//!   - wNAF: the scalar is rewritten with signed, odd digits smaller than 2^(w-1), with at least
+//!     w - 1 zeros between two non-zero digits. Negating a point is free, so we only precompute
+//!     P, 3P, ..., (2^(w-1) - 1)P, and add about one point every w + 1 bits instead of one every 2.
+//!   - Shamir's trick: a.P + b.Q with a single chain of doublings, instead of two.
+//!   - Straus: the same idea for a handful of points, with interleaved wNAFs.
+//!   - Pippenger: for many points, sorts them into buckets by scalar digit, window by window.
+//!
+//! Everything happens in Jacobian coordinates, with a single inversion at the end.
+
+use std::cmp::Ordering;
+
+use num_bigint::BigUint;
+
+use crate::element::FieldElement;
+use crate::jacobian::JacobianPoint;
+use crate::util::buint;
+use crate::{ECCError, Point};
+
+/// Width-w non-adjacent form of `scalar`, least significant digit first: every non-zero digit is
+/// odd, between -2^(w-1) and 2^(w-1), and followed by at least w - 1 zeros. w = 2 is the plain NAF.
+pub fn wnaf(scalar: &BigUint, w: usize) -> Result<Vec<i64>, ECCError> {
+    check_window(w)?;
+    Ok(wnaf_digits(scalar, w))
+}
+
+/// Non-adjacent form of `scalar` (digits in {-1, 0, 1}, no two adjacent non-zero digits),
+/// least significant digit first.
+pub fn naf(scalar: &BigUint) -> Vec<i64> {
+    wnaf_digits(scalar, 2)
+}
+
+impl<F: FieldElement> Point<F> {
+    /// Same as `mul`, with a width-w NAF of the scalar. w = 4 or 5 suits 256-bit scalars:
+    /// wider windows save additions, but the table of odd multiples grows as 2^(w-2).
+    pub fn mul_wnaf(&self, scalar: BigUint, w: usize) -> Result<Point<F>, ECCError> {
+        check_window(w)?;
+        Ok(interleaved_wnaf(std::slice::from_ref(self), &[scalar], w))
+    }
+
+    /// Same as `mul`, with the (width 2) NAF of the scalar.
+    pub fn mul_naf(&self, scalar: BigUint) -> Point<F> {
+        interleaved_wnaf(std::slice::from_ref(self), &[scalar], 2)
+    }
+}
+
+fn check_window(w: usize) -> Result<(), ECCError> {
+    if !(2..=16).contains(&w) {
+        return Err(ECCError::InvalidWindow);
+    }
+    Ok(())
+}
+
+// Assumes 2 <= w <= 16
+fn wnaf_digits(scalar: &BigUint, w: usize) -> Vec<i64> {
+    let modulus = 1i64 << w;
+    let mut k = scalar.clone();
+    let mut digits = vec![];
+    while k != buint(0) {
+        let mut digit = 0;
+        if k.bit(0) {
+            // k mod 2^w, taken between -2^(w-1) and 2^(w-1)
+            digit = (k.iter_u64_digits().next().unwrap() % modulus as u64) as i64;
+            if digit >= modulus / 2 {
+                digit -= modulus;
+            }
+            if digit > 0 {
+                k -= buint(digit as u64);
+            } else {
+                k += buint(digit.unsigned_abs());
+            }
+        }
+        digits.push(digit);
+        k >>= 1;
+    }
+    digits
+}
+
+/// a.P + b.Q with Shamir's trick: scan the bits of a and b together, doubling once per bit, and
+/// adding P, Q or the precomputed P + Q depending on the bits. ECDSA verification is exactly this.
+pub fn shamir<F: FieldElement>(
+    a: &BigUint,
+    p: &Point<F>,
+    b: &BigUint,
+    q: &Point<F>,
+) -> Result<Point<F>, ECCError> {
+    if p.curve != q.curve {
+        return Err(ECCError::CurveMismatch);
+    }
+    let jacobian_p = JacobianPoint::from(p.clone());
+    let jacobian_q = JacobianPoint::from(q.clone());
+    let sum = jacobian_p.clone() + jacobian_q.clone();
+
+    let mut result = JacobianPoint::infinity(&p.curve);
+    for i in (0..a.bits().max(b.bits())).rev() {
+        result = result.double();
+        match (a.bit(i), b.bit(i)) {
+            (true, true) => result = result + sum.clone(),
+            (true, false) => result = result + jacobian_p.clone(),
+            (false, true) => result = result + jacobian_q.clone(),
+            (false, false) => {}
+        }
+    }
+    Ok(result.to_affine())
+}
+
+/// k1.P1 + ... + kn.Pn with Straus' method: one table of odd multiples per point, and a single
+/// chain of doublings for the width-w NAFs of all the scalars. Best for a few points.
+pub fn straus<F: FieldElement>(
+    points: &[Point<F>],
+    scalars: &[BigUint],
+    w: usize,
+) -> Result<Point<F>, ECCError> {
+    check_inputs(points, scalars)?;
+    check_window(w)?;
+    Ok(interleaved_wnaf(points, scalars, w))
+}
+
+/// k1.P1 + ... + kn.Pn with Pippenger's bucket method. Scalars are cut into c-bit windows. For each
+/// window, every point goes into the bucket of its digit d, and the buckets B_d are combined with
+/// running sums: sum(d.B_d) = B_max + (B_max + B_max-1) + ... + (B_max + ... + B_1).
+/// That's n + 2^(c+1) additions per window, whatever the scalars: with c close to log2(n),
+/// b-bit scalars cost about b.n / log2(n) additions, against b.n / 2 for separate multiplications.
+pub fn pippenger<F: FieldElement>(
+    points: &[Point<F>],
+    scalars: &[BigUint],
+) -> Result<Point<F>, ECCError> {
+    check_inputs(points, scalars)?;
+    let c = window_size(points.len());
+    let bits = scalars.iter().map(BigUint::bits).max().unwrap_or(0) as usize;
+    let infinity = JacobianPoint::infinity(&points[0].curve);
+    let points: Vec<JacobianPoint<F>> = points.iter().cloned().map(JacobianPoint::from).collect();
+
+    let mut result = infinity.clone();
+    for window in (0..bits.div_ceil(c)).rev() {
+        for _ in 0..c {
+            result = result.double();
+        }
+        let mut buckets = vec![infinity.clone(); (1 << c) - 1];
+        for (point, scalar) in points.iter().zip(scalars) {
+            let digit = window_digit(scalar, window * c, c);
+            if digit != 0 {
+                buckets[digit - 1] = buckets[digit - 1].clone() + point.clone();
+            }
+        }
+        let mut running = infinity.clone();
+        let mut window_sum = infinity.clone();
+        for bucket in buckets.into_iter().rev() {
+            running = running + bucket;
+            window_sum = window_sum + running.clone();
+        }
+        result = result + window_sum;
+    }
+    Ok(result.to_affine())
+}
+
+fn check_inputs<F: FieldElement>(points: &[Point<F>], scalars: &[BigUint]) -> Result<(), ECCError> {
+    if points.len() != scalars.len() {
+        return Err(ECCError::MsmLengthMismatch);
+    }
+    if points.is_empty() {
+        return Err(ECCError::MsmEmptyInput);
+    }
+    if points.iter().any(|point| point.curve != points[0].curve) {
+        return Err(ECCError::CurveMismatch);
+    }
+    Ok(())
+}
+
+// Assumes at least one point, as many scalars as points, and 2 <= w <= 16
+fn interleaved_wnaf<F: FieldElement>(
+    points: &[Point<F>],
+    scalars: &[BigUint],
+    w: usize,
+) -> Point<F> {
+    let tables: Vec<Vec<JacobianPoint<F>>> = points
+        .iter()
+        .map(|point| odd_multiples(JacobianPoint::from(point.clone()), w))
+        .collect();
+    let digits: Vec<Vec<i64>> = scalars.iter().map(|k| wnaf_digits(k, w)).collect();
+    let len = digits.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut result = JacobianPoint::infinity(&points[0].curve);
+    for i in (0..len).rev() {
+        result = result.double();
+        for (table, digits) in tables.iter().zip(&digits) {
+            let digit = digits.get(i).copied().unwrap_or(0);
+            // |digit| is odd: |digit|.P is at index |digit| / 2
+            let multiple = || table[(digit.unsigned_abs() / 2) as usize].clone();
+            result = match digit.cmp(&0) {
+                Ordering::Greater => result + multiple(),
+                Ordering::Less => result + -multiple(),
+                Ordering::Equal => result,
+            };
+        }
+    }
+    result.to_affine()
+}
+
+// P, 3P, 5P, ..., (2^(w-1) - 1)P
+fn odd_multiples<F: FieldElement>(point: JacobianPoint<F>, w: usize) -> Vec<JacobianPoint<F>> {
+    let double = point.double();
+    let mut table = vec![point];
+    for i in 1..(1 << (w - 2)) {
+        table.push(table[i - 1].clone() + double.clone());
+    }
+    table
+}
+
+// Window size for Pippenger: roughly log2(n)
+fn window_size(n: usize) -> usize {
+    ((usize::BITS - n.leading_zeros()) as usize).clamp(2, 16)
+}
+
+// Bits start..start+c of the scalar
+fn window_digit(scalar: &BigUint, start: usize, c: usize) -> usize {
+    (0..c)
+        .filter(|&j| scalar.bit((start + j) as u64))
+        .map(|j| 1 << j)
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use proptest::prelude::*;
+
+    use crate::element::Element;
+    use crate::msm::{naf, pippenger, shamir, straus, wnaf};
+    use crate::util::{bint, buint};
+    use crate::{Curve, ECCError, Point};
+
+    fn from_digits(digits: &[i64]) -> BigUint {
+        let value = digits
+            .iter()
+            .rev()
+            .fold(bint(0), |acc, &digit| acc * bint(2) + bint(digit));
+        value.to_biguint().unwrap()
+    }
+
+    #[test]
+    fn test_naf() {
+        // 7 = 8 - 1
+        assert_eq!(naf(&buint(7)), vec![-1, 0, 0, 1]);
+        assert_eq!(naf(&buint(0)), Vec::<i64>::new());
+        // 255 = 256 - 1, in a single window of width 5
+        assert_eq!(
+            wnaf(&buint(255), 5).unwrap(),
+            vec![-1, 0, 0, 0, 0, 0, 0, 0, 1]
+        );
+        assert_eq!(wnaf(&buint(23), 4).unwrap(), vec![7, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_shamir_and_msm_on_toy_curve() {
+        // Y^2 = X^3 + 3X + 8 on F13: 9 points, generated by (1, 8)
+        let curve = Curve::named("itmc-13").unwrap();
+        let g = curve.generator().unwrap();
+        let points: Vec<Point> = (0u32..9).map(|i| g.mul(buint(i))).collect();
+        for (i, p) in points.iter().enumerate() {
+            for (j, q) in points.iter().enumerate() {
+                let (a, b) = (buint(i + 3), buint(2 * j));
+                let expected = p.mul(a.clone()) + q.mul(b.clone());
+                assert_eq!(shamir(&a, p, &b, q).unwrap(), expected);
+                let pair = [p.clone(), q.clone()];
+                let scalars = [a, b];
+                assert_eq!(straus(&pair, &scalars, 3).unwrap(), expected);
+                assert_eq!(pippenger(&pair, &scalars).unwrap(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_msm_errors() {
+        let c = Curve::named("itmc-13").unwrap();
+        let other = Curve::new("other", 0, 3, 8, 17);
+        let p = c.point(1, 8);
+        assert!(matches!(
+            pippenger(&[p.clone(), p.clone()], &[buint(1)]),
+            Err(ECCError::MsmLengthMismatch)
+        ));
+        assert!(matches!(
+            straus::<Element>(&[], &[], 4),
+            Err(ECCError::MsmEmptyInput)
+        ));
+        assert!(matches!(
+            shamir(&buint(1), &p, &buint(1), &other.point(1, 8)),
+            Err(ECCError::CurveMismatch)
+        ));
+        assert!(matches!(
+            pippenger(&[p.clone(), other.point(1, 8)], &[buint(1), buint(1)]),
+            Err(ECCError::CurveMismatch)
+        ));
+        assert!(matches!(wnaf(&buint(23), 1), Err(ECCError::InvalidWindow)));
+        assert!(matches!(wnaf(&buint(23), 17), Err(ECCError::InvalidWindow)));
+        assert!(matches!(
+            p.mul_wnaf(buint(3), 0),
+            Err(ECCError::InvalidWindow)
+        ));
+        assert!(matches!(
+            straus(&[p], &[buint(3)], 64),
+            Err(ECCError::InvalidWindow)
+        ));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn test_wnaf_digits(bytes in proptest::collection::vec(any::<u8>(), 0..40), w in 2usize..8) {
+            let k = BigUint::from_bytes_be(&bytes);
+            let digits = wnaf(&k, w).unwrap();
+            prop_assert_eq!(from_digits(&digits), k);
+            for (i, &digit) in digits.iter().enumerate() {
+                if digit != 0 {
+                    prop_assert!(digit % 2 != 0 && digit.abs() < 1 << (w - 1));
+                    prop_assert!(digits[i + 1..].iter().take(w - 1).all(|&d| d == 0));
+                }
+            }
+        }
+
+        // Random 256-bit scalars on secp256k1: all methods agree with double-and-add
+        #[test]
+        fn test_fast_mul_matches_mul(
+            a in proptest::collection::vec(any::<u8>(), 32),
+            b in proptest::collection::vec(any::<u8>(), 32),
+            c in proptest::collection::vec(any::<u8>(), 32),
+        ) {
+            let g = Curve::named("secp256k1").unwrap().generator().unwrap();
+            let (a, b, c) = (
+                BigUint::from_bytes_be(&a),
+                BigUint::from_bytes_be(&b),
+                BigUint::from_bytes_be(&c),
+            );
+            let q = g.mul(buint(0xc0ffee));
+            let r = g.mul(buint(0xdecaf));
+
+            prop_assert_eq!(g.mul_naf(a.clone()), g.mul(a.clone()));
+            prop_assert_eq!(g.mul_wnaf(a.clone(), 5).unwrap(), g.mul(a.clone()));
+
+            let expected = g.mul(a.clone()) + q.mul(b.clone());
+            prop_assert_eq!(shamir(&a, &g, &b, &q).unwrap(), expected.clone());
+
+            let expected = expected + r.mul(c.clone());
+            let points = [g, q, r];
+            let scalars = [a, b, c];
+            prop_assert_eq!(straus(&points, &scalars, 4).unwrap(), expected.clone());
+            prop_assert_eq!(pippenger(&points, &scalars).unwrap(), expected);
+        }
+    }
+}
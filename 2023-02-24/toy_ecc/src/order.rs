@@ -0,0 +1,327 @@
+//! Counting points on a curve, and computing the order of a point.
+//!
+//! Two strategies for #E(F_p):
+//!   - for tiny p, enumerate x in F_p: the Legendre symbol of x^3 + a2.x^2 + a4.x + a6 tells us
+//!     whether there are 0, 1 or 2 points with this x-coordinate. O(p).
+//!   - for medium p, Mestre's baby-step giant-step: by Hasse's theorem, #E = p + 1 - t with
+//!     |t| <= 2.sqrt(p). The order of any point narrows down the candidates in that interval, and
+//!     points on the quadratic twist (which has 2p + 2 - #E points) narrow them down further, until
+//!     a single one is left. O(p^(1/4)) group operations per point.
+//!
+//! Schoof's algorithm (polynomial time) isn't implemented: past ~2^40, use a real library.
+//!
+//! Point orders come from factoring a multiple of them by trial division, which is the actual
+//! bottleneck for medium p.
+
+use std::collections::HashMap;
+
+use num_bigint::BigUint;
+
+use crate::util::{bint, buint, factor, lcm, point_key};
+use crate::{Curve, ECCError, Point};
+
+/// Below this, we count points one x-coordinate at a time
+const NAIVE_COUNT_LIMIT: u64 = 1 << 16;
+
+impl Curve {
+    /// Number of points on the curve, including the point at infinity.
+    /// Free for curves with a generator, otherwise only practical for small p.
+    /// Errors like `order_bsgs` past the naive counting limit.
+    pub fn order(&self) -> Result<BigUint, ECCError> {
+        if let (Some(n), Some(h)) = (self.subgroup_order(), self.cofactor()) {
+            return Ok(n * h);
+        }
+        if self.p < buint(NAIVE_COUNT_LIMIT) {
+            Ok(self.order_naive())
+        } else {
+            self.order_bsgs()
+        }
+    }
+
+    /// Counts points with Legendre symbols, for each x in F_p.
+    pub fn order_naive(&self) -> BigUint {
+        let mut count = buint(1);
+        let mut x = buint(0);
+        while x < self.p {
+            count += match self.rhs(&self.element(bint(x.clone()))).legendre() {
+                0 => buint(1),
+                1 => buint(2),
+                _ => buint(0),
+            };
+            x += buint(1);
+        }
+        count
+    }
+
+    /// Counts points with Mestre's baby-step giant-step algorithm (see module documentation).
+    /// For p below ~230, the orders of points can't always pin #E down: we then fall back to `order_naive`.
+    /// Errors with `SingularCurve` on a singular curve, which Hasse's theorem doesn't cover, and
+    /// with `OrderNotFound` if no point order fits in the Hasse interval (p isn't prime).
+    pub fn order_bsgs(&self) -> Result<BigUint, ECCError> {
+        if !self.is_smooth() {
+            return Err(ECCError::SingularCurve);
+        }
+        let p = &self.p;
+        let d = self.non_square();
+        let twist = self.quadratic_twist_by(&d);
+        let twist_sum = (p + buint(1)) * buint(2);
+
+        // Hasse interval
+        let width = (p * buint(4)).sqrt();
+        let low = if p + buint(1) > width {
+            p + buint(1) - &width
+        } else {
+            buint(1)
+        };
+        let high = p + buint(1) + &width;
+
+        // #E must be a multiple of `exponent`, and #E' = 2p + 2 - #E a multiple of `twist_exponent`
+        let mut exponent = buint(1);
+        let mut twist_exponent = buint(1);
+        let mut x = buint(0);
+        while x < *p {
+            let x_elem = self.element(bint(x.clone()));
+            x += buint(1);
+            let rhs = self.rhs(&x_elem);
+            match rhs.legendre() {
+                1 => {
+                    let y = rhs.sqrt().unwrap();
+                    let point = self.point(bint(x_elem.value()), bint(y.value()));
+                    exponent = lcm(&exponent, &point.order_in_interval(&low, &high)?);
+                }
+                -1 => {
+                    // With d the twist's non-square, (d.x, d^2.y) is on the twist when d.y^2 = f(x)
+                    let twist_x = d.clone() * x_elem;
+                    let y = twist.rhs(&twist_x).sqrt().unwrap();
+                    let point = twist.point(bint(twist_x.value()), bint(y.value()));
+                    let (twist_low, twist_high) = (&twist_sum - &high, &twist_sum - &low);
+                    twist_exponent = lcm(
+                        &twist_exponent,
+                        &point.order_in_interval(&twist_low, &twist_high)?,
+                    );
+                }
+                _ => continue,
+            }
+
+            let mut candidates = vec![];
+            let mut n = (&low + &exponent - buint(1)) / &exponent * &exponent;
+            while n <= high && candidates.len() < 2 {
+                if (&twist_sum - &n) % &twist_exponent == buint(0) {
+                    candidates.push(n.clone());
+                }
+                n += &exponent;
+            }
+            if candidates.len() == 1 {
+                return Ok(candidates.pop().unwrap());
+            }
+        }
+        // Only possible for tiny p
+        Ok(self.order_naive())
+    }
+
+    /// Supersingular curves have p | t, where #E = p + 1 - t: for p > 3, this means #E = p + 1.
+    /// Their embedding degree is at most 6, which makes them pairing-friendly and MOV-vulnerable.
+    /// Singular curves aren't.
+    pub fn is_supersingular(&self) -> bool {
+        self.is_smooth()
+            && self
+                .order()
+                .is_ok_and(|order| order % &self.p == buint(1) % &self.p)
+    }
+
+    /// Iterates over all points of the curve, starting with the point at infinity.
+    pub fn points(&self) -> Points<'_> {
+        Points {
+            curve: self,
+            x: buint(0),
+            pending: Some(self.point_at_infinity()),
+        }
+    }
+}
+
+impl Point {
+    /// Smallest n > 0 such that [n]P = O. Computes the order of the whole curve first, and
+    /// errors like `Curve::order`.
+    pub fn order(&self) -> Result<BigUint, ECCError> {
+        Ok(self.order_from_multiple(&self.curve.order()?))
+    }
+
+    // Reduces a multiple of the order of P: removes prime factors for as long as we still get O
+    fn order_from_multiple(&self, multiple: &BigUint) -> BigUint {
+        let mut order = multiple.clone();
+        for (prime, exponent) in factor(multiple) {
+            for _ in 0..exponent {
+                let reduced = &order / &prime;
+                if self.mul(reduced.clone()).is_at_infinity() {
+                    order = reduced;
+                } else {
+                    break;
+                }
+            }
+        }
+        order
+    }
+
+    // Order of P, given that some multiple of it is in [low, high].
+    // Baby-step giant-step: finds n = low + i.s + j such that [low + i.s]P = -[j]P.
+    fn order_in_interval(&self, low: &BigUint, high: &BigUint) -> Result<BigUint, ECCError> {
+        let steps = (high - low).sqrt() + buint(1);
+
+        let mut baby_steps = HashMap::new();
+        let mut baby = self.curve.point_at_infinity();
+        let mut j = buint(0);
+        while j <= steps {
+            baby_steps
+                .entry(point_key(&-baby.clone()))
+                .or_insert(j.clone());
+            baby = baby + self.clone();
+            j += buint(1);
+        }
+
+        let giant_step = self.mul(steps.clone());
+        let mut giant = self.mul(low.clone());
+        let mut i = buint(0);
+        while i <= steps {
+            if let Some(j) = baby_steps.get(&point_key(&giant)) {
+                let multiple = low + &i * &steps + j;
+                return Ok(self.order_from_multiple(&multiple));
+            }
+            giant = giant + giant_step.clone();
+            i += buint(1);
+        }
+        Err(ECCError::OrderNotFound)
+    }
+}
+
+/// Iterator over the points of a curve, by increasing x-coordinate
+pub struct Points<'c> {
+    curve: &'c Curve,
+    x: BigUint,
+    pending: Option<Point>,
+}
+
+impl<'c> Iterator for Points<'c> {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(point) = self.pending.take() {
+            return Some(point);
+        }
+        while self.x < self.curve.p {
+            let x = self.curve.element(bint(self.x.clone()));
+            self.x += buint(1);
+            if let Some(y) = self.curve.rhs(&x).sqrt() {
+                let point = self.curve.point(bint(x.value()), bint(y.value()));
+                if !y.is_zero() {
+                    self.pending = Some(-point.clone());
+                }
+                return Some(point);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::util::buint;
+    use crate::{Curve, ECCError};
+
+    #[test]
+    fn test_textbook_orders() {
+        // Washington, "Elliptic Curves: Number Theory and Cryptography", example 4.1
+        let c = Curve::new("c", 0, 3, 8, 13);
+        assert_eq!(c.order().unwrap(), buint(9));
+        // y^2 = x^3 + 2x + 2 on F17: 19 points, so (5, 1) generates the whole group
+        let c = Curve::new("c", 0, 2, 2, 17);
+        assert_eq!(c.order().unwrap(), buint(19));
+        assert_eq!(c.point(5, 1).order().unwrap(), buint(19));
+        // The curves used in the ECDH tests
+        assert_eq!(
+            Curve::new("c", 0, 1, 28, 10007).order().unwrap(),
+            buint(9851)
+        );
+        assert_eq!(
+            Curve::new("c", 0, 1, 24, 10007).order().unwrap(),
+            buint(10056)
+        );
+        assert_eq!(
+            Curve::new("c", 0, 1, 54, 10007).order().unwrap(),
+            buint(10090)
+        );
+    }
+
+    #[test]
+    fn test_point_order() {
+        // 10056 = 2^3 * 3 * 419
+        let c = Curve::new("c", 0, 1, 24, 10007);
+        let p = c.point(1, 1158);
+        assert_eq!(p.order().unwrap(), buint(5028));
+        assert_eq!(p.mul(buint(24)).order().unwrap(), buint(419));
+        assert_eq!(p.mul(buint(1257)).order().unwrap(), buint(4));
+        assert_eq!(c.point_at_infinity().order().unwrap(), buint(1));
+    }
+
+    #[test]
+    fn test_bsgs_matches_naive_count() {
+        // Weierstrass, Montgomery and general forms, for p = 1 and 3 (mod 4)
+        for p in [1019, 1013] {
+            for b in 1..6 {
+                for (a2, a4) in [(0, 1), (5, 0), (2, 3)] {
+                    let c = Curve::new("c", a2, a4, b, p);
+                    if c.is_smooth() {
+                        assert_eq!(c.order_bsgs().unwrap(), c.order_naive(), "{}", c);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_medium_prime_order() {
+        // 999853 points (a prime), counted with Legendre symbols
+        let c = Curve::new("c", 0, 3, 7, 1000003);
+        assert_eq!(c.order().unwrap(), buint(999853));
+        let g = c.points().nth(1).unwrap();
+        assert_eq!(g.order().unwrap(), buint(999853));
+    }
+
+    #[test]
+    fn test_points_iterator() {
+        let c = Curve::new("c", 0, 3, 8, 13);
+        let points: Vec<_> = c.points().collect();
+        assert_eq!(buint(points.len()), c.order().unwrap());
+        assert!(points[0].is_at_infinity());
+        assert!(points.iter().all(|p| c.contains(p.clone())));
+        let distinct: HashSet<_> = points.iter().map(|p| p.to_string()).collect();
+        assert_eq!(distinct.len(), points.len());
+
+        // p = 1 (mod 4) goes through Tonelli-Shanks
+        let c = Curve::new("c", 0, 1, 3, 10009);
+        assert_eq!(buint(c.points().count()), c.order().unwrap());
+
+        // With a6 = 0, (0, 0) is a point like any other
+        let c = Curve::new("c", 0, 1, 0, 59);
+        assert_eq!(buint(c.points().count()), c.order().unwrap());
+        let origin = c.points().find(|p| p == &c.point(0, 0)).unwrap();
+        assert_eq!(origin.order().unwrap(), buint(2));
+    }
+
+    #[test]
+    fn test_singular_curve_order() {
+        // y^2 = x^3 - 3x + 2 = (x - 1)^2 (x + 2) has a node: no Hasse bound
+        let node = Curve::new("node", 0, -3, 2, 65537);
+        assert!(!node.is_smooth());
+        assert!(matches!(node.order(), Err(ECCError::SingularCurve)));
+        assert!(matches!(
+            node.point(2, 2).order(),
+            Err(ECCError::SingularCurve)
+        ));
+        assert!(!node.is_supersingular());
+        // Below the naive counting limit, points can still be counted
+        let small_node = Curve::new("node", 0, -3, 2, 13);
+        assert_eq!(small_node.order().unwrap(), small_node.order_naive());
+    }
+}
@@ -0,0 +1,336 @@
+//! Weil and Tate pairings, with Miller's algorithm.
+//!
+//! For P of order n, f_P is the function with divisor n[P] - n[O]. Miller's algorithm evaluates it
+//! at Q with a double-and-add over the bits of n: each step multiplies by the line through T and P
+//! (or the tangent at T), divided by the vertical line through their sum.
+//!
+//!   - Weil: e_n(P, Q) = (-1)^n . f_P(Q) / f_Q(P), for P and Q in E[n]. It's bilinear, alternating,
+//!     and non-degenerate, but needs all of E[n]: on a curve over F_q, that's E(F_q^k) with k the
+//!     embedding degree of n (see `extension::embedding_degree`).
+//!   - Tate: t_n(P, Q) = f_P(Q)^((q^k - 1) / n), for P in E[n] and Q in E(F_q^k). The final
+//!     exponentiation maps the result to the n-th roots of unity, where it's well-defined. It costs
+//!     a single Miller loop.
+//!
+//! f_P is normalized at O (its leading term in x/y is 1), which is what makes these formulas hold
+//! without an auxiliary point.
+//!
+//! Pairings move discrete logs from E to F_q^k*: that's the MOV attack, in `mov_attack`. It's
+//! practical when k is small, e.g. on supersingular curves.
+
+use std::collections::HashMap;
+
+use num_bigint::BigUint;
+
+use crate::ecdlp::{ceil_sqrt, Dlog};
+use crate::element::FieldElement;
+use crate::extension::ExtElement;
+use crate::util::buint;
+use crate::{ECCError, Point};
+
+/// Miller's algorithm: f(Q), with div(f) = n[P] - [nP] - (n - 1)[O], normalized at O.
+/// Returns `ECCError::NotInvertible` when Q is a zero or a pole of one of the lines along the way,
+/// which can only happen when Q is a multiple of P.
+pub fn miller<F: FieldElement>(p: &Point<F>, q: &Point<F>, n: &BigUint) -> Result<F, ECCError> {
+    if p.curve != q.curve {
+        return Err(ECCError::CurveMismatch);
+    }
+    if n == &buint(0) {
+        return Err(ECCError::InvalidScalar);
+    }
+    let mut t = p.clone();
+    let mut f = p.curve.element(1);
+    for i in (0..n.bits() - 1).rev() {
+        f = f.pow(2).checked_mul(&line(&t, &t, q)?)?;
+        t = t.checked_add(&t)?;
+        if n.bit(i) {
+            f = f.checked_mul(&line(&t, p, q)?)?;
+            t = t.checked_add(p)?;
+        }
+    }
+    Ok(f)
+}
+
+/// Weil pairing of P and Q in E[n]: an n-th root of unity, which is 1 if and only if P and Q are
+/// linearly dependent.
+pub fn weil_pairing<F: FieldElement>(
+    p: &Point<F>,
+    q: &Point<F>,
+    n: &BigUint,
+) -> Result<F, ECCError> {
+    if p.curve != q.curve {
+        return Err(ECCError::CurveMismatch);
+    }
+    check_torsion(p, n)?;
+    check_torsion(q, n)?;
+    let one = p.curve.element(1);
+    if p.is_at_infinity() || q.is_at_infinity() || p == q {
+        return Ok(one);
+    }
+    match (miller(p, q, n), miller(q, p, n)) {
+        (Ok(f_p), Ok(f_q)) => {
+            let e = f_p.checked_div(&f_q)?;
+            Ok(if n.bit(0) { -e } else { e })
+        }
+        // Q is a multiple of P
+        _ => Ok(one),
+    }
+}
+
+/// Reduced Tate pairing of P in E[n] and Q: an n-th root of unity. n must divide q^k - 1, where
+/// q^k is the size of the field the curve is defined over.
+/// Q can't be a multiple of P: take it outside of E(F_q) when k > 1, e.g. with a distortion map.
+pub fn tate_pairing<F: FieldElement>(
+    p: &Point<F>,
+    q: &Point<F>,
+    n: &BigUint,
+) -> Result<F, ECCError> {
+    check_torsion(p, n)?;
+    let group_order = p.curve.element(1).field_order() - buint(1);
+    if &group_order % n != buint(0) {
+        return Err(ECCError::MissingRootsOfUnity);
+    }
+    if p.is_at_infinity() || q.is_at_infinity() {
+        return Ok(p.curve.element(1));
+    }
+    let f = miller(p, q, n).map_err(|e| match e {
+        ECCError::NotInvertible => ECCError::PairingEvaluationFailed,
+        e => e,
+    })?;
+    Ok(f.pow_big(&(group_order / n)))
+}
+
+/// The MOV attack: solves Q = [k]P on E(F_q), where P has order n, as a discrete log in F_q^k*.
+///
+/// `t` is a point of order n on E(F_q^k), independent from P. The Weil pairing maps P and Q to
+/// a = e_n(P, T) and b = e_n(Q, T) = a^k, and baby-step giant-step finds k in F_q^k*.
+/// The iteration count is the number of multiplications in F_q^k.
+pub fn mov_attack(
+    p: &Point,
+    q: &Point,
+    n: &BigUint,
+    t: &Point<ExtElement>,
+) -> Result<Dlog, ECCError> {
+    let a = weil_pairing(&p.lift(&t.curve)?, t, n)?;
+    let b = weil_pairing(&q.lift(&t.curve)?, t, n)?;
+    let one = t.curve.element(1);
+    if a == one {
+        return Err(ECCError::AttackFailed);
+    }
+
+    // Same as `ecdlp::baby_step_giant_step`, multiplicatively: b.a^(-i.m) = a^j
+    let m = ceil_sqrt(n);
+    let mut iterations = 0;
+    let mut baby_steps = HashMap::new();
+    let mut baby = one;
+    let mut j = buint(0);
+    while j < m {
+        baby_steps.entry(key(&baby)).or_insert(j.clone());
+        baby = baby * a.clone();
+        j += buint(1);
+        iterations += 1;
+    }
+
+    let giant_step = a.pow_big(&(n - &m % n));
+    let mut giant = b;
+    let mut i = buint(0);
+    while i < m {
+        if let Some(j) = baby_steps.get(&key(&giant)) {
+            let k = (&i * &m + j) % n;
+            return Ok(Dlog { k, iterations });
+        }
+        giant = giant * giant_step.clone();
+        i += buint(1);
+        iterations += 1;
+    }
+    Err(ECCError::NoDiscreteLog)
+}
+
+// g(Q), for g the line through T and R divided by the vertical line through T + R
+fn line<F: FieldElement>(t: &Point<F>, r: &Point<F>, q: &Point<F>) -> Result<F, ECCError> {
+    let c = &t.curve;
+    if t.is_at_infinity() || r.is_at_infinity() {
+        return Ok(c.element(1));
+    }
+    if t.x == r.x && t.y.checked_add(&r.y)?.is_zero() {
+        // T + R = O: the line itself is vertical
+        return q.x.checked_sub(&t.x);
+    }
+    let lambda = if t == r {
+        (c.element(3) * t.x.pow(2) + c.element(2) * c.a2.clone() * t.x.clone() + c.a4.clone())
+            .checked_div(&(c.element(2) * t.y.clone()))?
+    } else {
+        (r.y.clone() - t.y.clone()).checked_div(&(r.x.clone() - t.x.clone()))?
+    };
+    let numerator = q.y.clone() - t.y.clone() - lambda.clone() * (q.x.clone() - t.x.clone());
+    // x(T + R) = lambda^2 - a2 - x(T) - x(R)
+    let denominator = q.x.clone() + t.x.clone() + r.x.clone() + c.a2.clone() - lambda.pow(2);
+    numerator.checked_div(&denominator)
+}
+
+fn check_torsion<F: FieldElement>(point: &Point<F>, n: &BigUint) -> Result<(), ECCError> {
+    if !point.mul(n.clone()).is_at_infinity() {
+        return Err(ECCError::NotTorsionPoint);
+    }
+    Ok(())
+}
+
+fn key(element: &ExtElement) -> Vec<BigUint> {
+    element.coefficients().iter().map(|c| c.value()).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ecdlp::baby_step_giant_step;
+    use crate::element::Element;
+    use crate::extension::{ExtElement, ExtensionField};
+    use crate::pairing::{mov_attack, tate_pairing, weil_pairing};
+    use crate::util::buint;
+    use crate::{Curve, ECCError, Point};
+
+    // (x, y) -> (-x, iy) maps y^2 = x^3 + x to itself, and E(F_p) out of itself when p = 3 (mod 4)
+    fn distortion(curve: &Curve<ExtElement>, point: &Point<ExtElement>) -> Point<ExtElement> {
+        let i = ExtElement::new(&[0, 1], point.x.field());
+        curve
+            .point_from_elements(-point.x.clone(), i * point.y.clone())
+            .unwrap()
+    }
+
+    // A point of order n on curve, when #E = cofactor.n
+    fn point_of_order(curve: &Curve, cofactor: u32) -> Point {
+        curve
+            .points()
+            .map(|point| point.mul(buint(cofactor)))
+            .find(|point| !point.is_at_infinity())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_weil_pairing_textbook() {
+        // Hoffstein, Pipher, Silverman, "An Introduction to Mathematical Cryptography":
+        // E[5] is in E(F_631) for y^2 = x^3 + 30x + 34
+        let curve = Curve::new("c", 0, 30, 34, 631);
+        let p = curve.point(36, 60);
+        let q = curve.point(121, 387);
+        let n = buint(5);
+        assert_eq!(weil_pairing(&p, &q, &n).unwrap(), Element::new(242, 631));
+
+        let p3 = curve.point(617, 5);
+        let q4 = curve.point(121, 244);
+        assert_eq!(p3, p.mul(buint(3)));
+        assert_eq!(q4, q.mul(buint(4)));
+        assert_eq!(weil_pairing(&p3, &q4, &n).unwrap(), Element::new(512, 631));
+        assert_eq!(Element::new(242, 631).pow(12), Element::new(512, 631));
+
+        // Tate pairing, with k = 1
+        let tate = tate_pairing(&p, &q, &n).unwrap();
+        assert_eq!(tate.pow(5), curve.element(1));
+        assert_ne!(tate, curve.element(1));
+        assert_eq!(tate_pairing(&p3, &q4, &n).unwrap(), tate.pow(12));
+    }
+
+    #[test]
+    fn test_weil_pairing_properties() {
+        let curve = Curve::new("c", 0, 1, 0, 59);
+        let f = ExtensionField::new(59, &[1, 0, 1]).unwrap();
+        let lifted = curve.lift(&f).unwrap();
+        let n = buint(5);
+        let p = point_of_order(&curve, 12).lift(&lifted).unwrap();
+        let q = distortion(&lifted, &p);
+        let one = lifted.element(1);
+
+        let e = weil_pairing(&p, &q, &n).unwrap();
+        // Non-degenerate, and a 5th root of unity
+        assert_ne!(e, one);
+        assert_eq!(e.pow(5), one);
+        // Alternating
+        assert_eq!(weil_pairing(&p, &p, &n).unwrap(), one);
+        assert_eq!(weil_pairing(&p, &p.mul(buint(3)), &n).unwrap(), one);
+        assert_eq!(weil_pairing(&q, &p, &n).unwrap() * e.clone(), one);
+        // Bilinear
+        for a in 1..5usize {
+            for b in 1..5usize {
+                assert_eq!(
+                    weil_pairing(&p.mul(buint(a)), &q.mul(buint(b)), &n).unwrap(),
+                    e.pow(a * b)
+                );
+            }
+        }
+        assert_eq!(
+            weil_pairing(&(p.clone() + p.mul(buint(2))), &q, &n).unwrap(),
+            weil_pairing(&p, &q, &n).unwrap() * weil_pairing(&p.mul(buint(2)), &q, &n).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_tate_pairing_properties() {
+        let curve = Curve::new("c", 0, 1, 0, 59);
+        let f = ExtensionField::new(59, &[1, 0, 1]).unwrap();
+        let lifted = curve.lift(&f).unwrap();
+        let n = buint(5);
+        let p = point_of_order(&curve, 12).lift(&lifted).unwrap();
+        let q = distortion(&lifted, &p);
+        let one = lifted.element(1);
+
+        let t = tate_pairing(&p, &q, &n).unwrap();
+        assert_ne!(t, one);
+        assert_eq!(t.pow(5), one);
+        for a in 1..5usize {
+            for b in 1..5usize {
+                assert_eq!(
+                    tate_pairing(&p.mul(buint(a)), &q.mul(buint(b)), &n).unwrap(),
+                    t.pow(a * b)
+                );
+            }
+        }
+        // Q is a multiple of P: f_P has a zero or a pole there
+        assert!(matches!(
+            tate_pairing(&p, &p.mul(buint(2)), &n),
+            Err(ECCError::PairingEvaluationFailed)
+        ));
+    }
+
+    #[test]
+    fn test_pairing_errors() {
+        let curve = Curve::new("c", 0, 30, 34, 631);
+        let p = curve.point(36, 60);
+        let q = curve.point(121, 387);
+        assert!(matches!(
+            weil_pairing(&p, &q, &buint(7)),
+            Err(ECCError::NotTorsionPoint)
+        ));
+        // 5 doesn't divide 59 - 1: E(F_59)[5] only pairs into F_59^2
+        let curve = Curve::new("c", 0, 1, 0, 59);
+        let p = point_of_order(&curve, 12);
+        assert!(matches!(
+            tate_pairing(&p, &p, &buint(5)),
+            Err(ECCError::MissingRootsOfUnity)
+        ));
+    }
+
+    #[test]
+    fn test_mov_attack() {
+        // y^2 = x^3 + x over F_p, p = 3 (mod 4): supersingular, with p + 1 = 4 * 250043 points
+        let p = 1000171;
+        let curve = Curve::new("supersingular", 0, 1, 0, p);
+        assert!(curve.is_supersingular());
+        let n = buint(250043u32);
+        let g = point_of_order(&curve, 4);
+        let k = buint(123456u32);
+        let q = g.mul(k.clone());
+
+        let f = ExtensionField::new(p, &[1, 0, 1]).unwrap();
+        let lifted = curve.lift(&f).unwrap();
+        let t = distortion(&lifted, &g.lift(&lifted).unwrap());
+        let dlog = mov_attack(&g, &q, &n, &t).unwrap();
+        assert_eq!(dlog.k, k);
+        // Same amount of work as on the curve, but in F_p^2: sub-exponential algorithms apply there
+        assert_eq!(baby_step_giant_step(&g, &q, &n).unwrap().k, k);
+
+        // T in <P>: the pairing is degenerate
+        let t = g.mul(buint(2)).lift(&lifted).unwrap();
+        assert!(matches!(
+            mov_attack(&g, &q, &n, &t),
+            Err(ECCError::AttackFailed)
+        ));
+    }
+}
@@ -0,0 +1,791 @@
+//! Polynomials over F_p.
+//!
+//! Coefficients are stored lowest degree first, without leading zeros: the zero polynomial has no
+//! coefficients, and no degree. Over a field, polynomials behave much like integers:
+//!   - Euclidean division, hence GCDs and arithmetic modulo a polynomial (see `ExtensionField`)
+//!   - unique factorization into monic irreducible polynomials
+//!
+//! Factoring runs in three steps, all based on x^(p^d) - x being the product of the monic
+//! irreducible polynomials whose degree divides d:
+//!   - square-free factorization: gcd(f, f') contains the repeated factors
+//!   - distinct-degree factorization: gcd(f, x^(p^d) - x) contains the factors of degree d
+//!   - equal-degree factorization (Cantor-Zassenhaus): a product g of factors of degree d splits
+//!     with gcd(g, a^((p^d - 1)/2) - 1), for about half of the polynomials a. For p = 2, the trace
+//!     a + a^2 + ... + a^(2^(d-1)) plays the same role.
+//!
+//! Roots are the factors of degree 1. Irreducibility uses Rabin's test instead, which doesn't need
+//! to split anything.
+
+use std::fmt;
+use std::ops;
+use std::sync::Arc;
+
+use num_bigint::{BigUint, ToBigInt};
+
+use crate::element::{Element, Field};
+use crate::util::{bint, buint, factor};
+use crate::ECCError;
+
+/// c0 + c1.x + ... + cn.x^n, with coefficients in F_p
+#[derive(Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    // Lowest degree first, without leading zeros
+    coefficients: Vec<Element>,
+    field: Arc<Field>,
+}
+
+impl Polynomial {
+    /// A polynomial from its coefficients, lowest degree first: [3, 0, 1] is x^2 + 3.
+    /// Coefficients are reduced mod p.
+    pub fn new<T: ToBigInt>(coefficients: &[T], field: &Arc<Field>) -> Self {
+        let coefficients = coefficients
+            .iter()
+            .map(|c| Element::in_field(c.to_bigint().unwrap(), field))
+            .collect();
+        Self::from_trusted(coefficients, field)
+    }
+
+    /// Same as `new`, with coefficients which are already elements of F_p
+    pub fn from_elements(coefficients: Vec<Element>, field: &Arc<Field>) -> Result<Self, ECCError> {
+        if coefficients.iter().any(|c| c.field() != field) {
+            return Err(ECCError::ModulusMismatch);
+        }
+        Ok(Self::from_trusted(coefficients, field))
+    }
+
+    pub fn zero(field: &Arc<Field>) -> Self {
+        Self::from_trusted(vec![], field)
+    }
+
+    pub fn constant(c: Element) -> Self {
+        let field = c.field().clone();
+        Self::from_trusted(vec![c], &field)
+    }
+
+    /// The polynomial x
+    pub fn x(field: &Arc<Field>) -> Self {
+        Self::new(&[0, 1], field)
+    }
+
+    /// The unique polynomial of degree < n through n points with distinct x-coordinates, with
+    /// Lagrange's formula: the sum of y_i.prod((x - x_j) / (x_i - x_j)) over j != i.
+    /// Errors with `NotInvertible` if two x-coordinates are equal.
+    pub fn interpolate(
+        points: &[(Element, Element)],
+        field: &Arc<Field>,
+    ) -> Result<Self, ECCError> {
+        let mut result = Self::zero(field);
+        for (i, (xi, yi)) in points.iter().enumerate() {
+            let mut basis = Self::from_elements(vec![yi.clone()], field)?;
+            for (j, (xj, _)) in points.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                let scale = xi.checked_sub(xj)?.inverse()?;
+                let factor = Self::from_elements(vec![-xj.clone() * scale.clone(), scale], field)?;
+                basis = basis * factor;
+            }
+            result = result + basis;
+        }
+        Ok(result)
+    }
+
+    /// Coefficients, lowest degree first. Empty for the zero polynomial.
+    pub fn coefficients(&self) -> &[Element] {
+        &self.coefficients
+    }
+
+    pub fn field(&self) -> &Arc<Field> {
+        &self.field
+    }
+
+    /// None for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn leading_coefficient(&self) -> Option<&Element> {
+        self.coefficients.last()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    /// Divides by the leading coefficient. Zero stays zero.
+    pub fn monic(&self) -> Self {
+        match self.leading_coefficient() {
+            None => self.clone(),
+            Some(leading) => {
+                let inverse = leading.inverse().unwrap();
+                self.scale(&inverse)
+            }
+        }
+    }
+
+    /// f(x), with Horner's method. Panics if x is in another field.
+    pub fn evaluate(&self, x: &Element) -> Element {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Element::in_field(0, &self.field), |acc, c| {
+                acc * x.clone() + c.clone()
+            })
+    }
+
+    /// Formal derivative: the sum of i.c_i.x^(i - 1). It's zero for polynomials in x^p.
+    pub fn derivative(&self) -> Self {
+        let coefficients = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, c)| Element::in_field(i, &self.field) * c.clone())
+            .collect();
+        Self::from_trusted(coefficients, &self.field)
+    }
+
+    /// Same as `+`, but returns an error instead of panicking when the fields differ
+    pub fn checked_add(&self, other: &Self) -> Result<Self, ECCError> {
+        self.check_field(other)?;
+        Ok(self.add_scaled(other, &Element::in_field(1, &self.field)))
+    }
+
+    /// Same as `-`, but returns an error instead of panicking when the fields differ
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, ECCError> {
+        self.check_field(other)?;
+        Ok(self.add_scaled(other, &Element::in_field(-1, &self.field)))
+    }
+
+    /// Same as `*`, but returns an error instead of panicking when the fields differ
+    pub fn checked_mul(&self, other: &Self) -> Result<Self, ECCError> {
+        self.check_field(other)?;
+        if self.is_zero() || other.is_zero() {
+            return Ok(Self::zero(&self.field));
+        }
+        let zero = Element::in_field(0, &self.field);
+        let mut result = vec![zero; self.coefficients.len() + other.coefficients.len() - 1];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in other.coefficients.iter().enumerate() {
+                result[i + j] = result[i + j].clone() + a.clone() * b.clone();
+            }
+        }
+        Ok(Self::from_trusted(result, &self.field))
+    }
+
+    /// Euclidean division: (q, r) with self = q.divisor + r, and deg(r) < deg(divisor).
+    /// Errors with `NotInvertible` if the divisor is zero.
+    pub fn divmod(&self, divisor: &Self) -> Result<(Self, Self), ECCError> {
+        self.check_field(divisor)?;
+        let leading_inverse = divisor
+            .leading_coefficient()
+            .ok_or(ECCError::NotInvertible)?
+            .inverse()?;
+        let mut remainder = self.coefficients.clone();
+        let n = divisor.coefficients.len();
+        if remainder.len() < n {
+            return Ok((Self::zero(&self.field), self.clone()));
+        }
+        let mut quotient = vec![Element::in_field(0, &self.field); remainder.len() - n + 1];
+        for shift in (0..quotient.len()).rev() {
+            let c = remainder[shift + n - 1].clone() * leading_inverse.clone();
+            for (i, d) in divisor.coefficients.iter().enumerate() {
+                remainder[shift + i] = remainder[shift + i].clone() - c.clone() * d.clone();
+            }
+            quotient[shift] = c;
+        }
+        Ok((
+            Self::from_trusted(quotient, &self.field),
+            Self::from_trusted(remainder, &self.field),
+        ))
+    }
+
+    /// The monic GCD, with Euclid's algorithm. gcd(0, 0) = 0.
+    pub fn gcd(&self, other: &Self) -> Result<Self, ECCError> {
+        self.check_field(other)?;
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let r = a.divmod(&b)?.1;
+            a = b;
+            b = r;
+        }
+        Ok(a.monic())
+    }
+
+    /// self^exponent mod modulus, with square-and-multiply.
+    /// Errors with `NotInvertible` if the modulus is zero.
+    pub fn pow_mod(&self, exponent: &BigUint, modulus: &Self) -> Result<Self, ECCError> {
+        let base = self.divmod(modulus)?.1;
+        let mut result = Self::one(&self.field).divmod(modulus)?.1;
+        for i in (0..exponent.bits()).rev() {
+            result = (result.clone() * result).divmod(modulus)?.1;
+            if exponent.bit(i) {
+                result = (result * base.clone()).divmod(modulus)?.1;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Distinct roots in F_p, sorted by value. The zero polynomial gets none.
+    pub fn roots(&self) -> Vec<Element> {
+        if self.is_zero() {
+            return vec![];
+        }
+        // gcd(f, x^p - x) is the product of the x - r for the roots r
+        let x = Self::x(&self.field);
+        let frobenius = x.pow_mod(self.field.modulus(), self).unwrap();
+        let linear = self.gcd(&(frobenius - x)).unwrap();
+        let mut roots: Vec<Element> = linear
+            .equal_degree_factors(1)
+            .into_iter()
+            .map(|factor| -factor.coefficients[0].clone())
+            .collect();
+        roots.sort_by_key(|root| root.value());
+        roots
+    }
+
+    /// Monic irreducible factors and their multiplicities, sorted by degree then coefficients.
+    /// The leading coefficient is dropped. The zero polynomial and constants have no factors.
+    pub fn factor(&self) -> Vec<(Polynomial, u32)> {
+        let mut factors = vec![];
+        for (square_free, multiplicity) in self.square_free_factors() {
+            for (product, degree) in square_free.distinct_degree_factors() {
+                for factor in product.equal_degree_factors(degree) {
+                    factors.push((factor, multiplicity));
+                }
+            }
+        }
+        factors.sort_by_key(|(factor, _)| {
+            let values: Vec<BigUint> = factor.coefficients.iter().map(|c| c.value()).collect();
+            (factor.coefficients.len(), values)
+        });
+        factors
+    }
+
+    /// Rabin's test: f of degree k is irreducible over F_p iff x^(p^k) = x (mod f), and
+    /// gcd(x^(p^(k/r)) - x, f) = 1 for every prime r dividing k. Constants aren't irreducible.
+    pub fn is_irreducible(&self) -> bool {
+        let k = match self.degree() {
+            None | Some(0) => return false,
+            Some(k) => k,
+        };
+        let p = self.field.modulus();
+        let x = Self::x(&self.field).divmod(self).unwrap().1;
+        let maximal_divisors: Vec<usize> = factor(&buint(k))
+            .into_iter()
+            .map(|(r, _)| k / r.to_u32_digits()[0] as usize)
+            .collect();
+
+        // x^(p^i) mod f, for i = 1..k
+        let mut frobenius = x.clone();
+        for i in 1..=k {
+            frobenius = frobenius.pow_mod(p, self).unwrap();
+            if maximal_divisors.contains(&i)
+                && self.gcd(&(frobenius.clone() - x.clone())).unwrap().degree() != Some(0)
+            {
+                return false;
+            }
+        }
+        frobenius == x
+    }
+
+    /// "3x^2 + x + 5", with any name for the variable
+    pub fn format_with(&self, variable: &str) -> String {
+        let terms: Vec<String> = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, c)| !c.is_zero())
+            .map(|(i, c)| {
+                let coefficient = if c.value() == buint(1) && i > 0 {
+                    String::new()
+                } else {
+                    c.value().to_string()
+                };
+                match i {
+                    0 => coefficient,
+                    1 => format!("{coefficient}{variable}"),
+                    _ => format!("{coefficient}{variable}^{i}"),
+                }
+            })
+            .collect();
+        if terms.is_empty() {
+            "0".to_string()
+        } else {
+            terms.join(" + ")
+        }
+    }
+
+    fn one(field: &Arc<Field>) -> Self {
+        Self::new(&[1], field)
+    }
+
+    // Coefficients must be in `field`
+    fn from_trusted(mut coefficients: Vec<Element>, field: &Arc<Field>) -> Self {
+        while coefficients.last().is_some_and(|c| c.is_zero()) {
+            coefficients.pop();
+        }
+        Self {
+            coefficients,
+            field: field.clone(),
+        }
+    }
+
+    fn scale(&self, c: &Element) -> Self {
+        let coefficients = self
+            .coefficients
+            .iter()
+            .map(|a| a.clone() * c.clone())
+            .collect();
+        Self::from_trusted(coefficients, &self.field)
+    }
+
+    // self + c.other
+    fn add_scaled(&self, other: &Self, c: &Element) -> Self {
+        let mut result = self.coefficients.clone();
+        for (i, b) in other.coefficients.iter().enumerate() {
+            let term = c.clone() * b.clone();
+            if i < result.len() {
+                result[i] = result[i].clone() + term;
+            } else {
+                result.push(term);
+            }
+        }
+        Self::from_trusted(result, &self.field)
+    }
+
+    fn check_field(&self, other: &Self) -> Result<(), ECCError> {
+        if !Arc::ptr_eq(&self.field, &other.field) && self.field != other.field {
+            return Err(ECCError::ModulusMismatch);
+        }
+        Ok(())
+    }
+
+    // f = prod(g_i^m_i), with the g_i square-free and pairwise coprime (not necessarily irreducible)
+    fn square_free_factors(&self) -> Vec<(Polynomial, u32)> {
+        if self.degree().unwrap_or(0) == 0 {
+            return vec![];
+        }
+        let f = self.monic();
+        let one = Self::one(&self.field);
+        let mut factors = vec![];
+
+        // c holds the repeated factors, w the factors of multiplicity >= i not divisible by p
+        let mut c = f.gcd(&f.derivative()).unwrap();
+        let mut w = f.divmod(&c).unwrap().0;
+        let mut i = 1;
+        while w != one {
+            let y = w.gcd(&c).unwrap();
+            let factor = w.divmod(&y).unwrap().0;
+            if factor != one {
+                factors.push((factor, i));
+            }
+            w = y.clone();
+            c = c.divmod(&y).unwrap().0;
+            i += 1;
+        }
+
+        // What's left is a polynomial in x^p: its p-th root has coefficients c_(i.p), since
+        // a^p = a in F_p
+        if c != one {
+            let p = self.field.modulus().to_u64_digits()[0] as usize;
+            let root_coefficients = c.coefficients.iter().step_by(p).cloned().collect();
+            let root = Self::from_trusted(root_coefficients, &self.field);
+            for (factor, multiplicity) in root.square_free_factors() {
+                factors.push((factor, multiplicity * p as u32));
+            }
+        }
+        factors
+    }
+
+    // For a monic square-free f: the products of its irreducible factors of each degree d
+    fn distinct_degree_factors(&self) -> Vec<(Polynomial, usize)> {
+        let x = Self::x(&self.field);
+        let mut f = self.clone();
+        let mut factors = vec![];
+        // x^(p^d) mod f
+        let mut frobenius = x.clone();
+        let mut d = 1;
+        while f.degree().unwrap() >= 2 * d {
+            frobenius = frobenius.pow_mod(self.field.modulus(), &f).unwrap();
+            let g = f.gcd(&(frobenius.clone() - x.clone())).unwrap();
+            if g.degree() != Some(0) {
+                f = f.divmod(&g).unwrap().0;
+                frobenius = frobenius.divmod(&f).unwrap().1;
+                factors.push((g, d));
+            }
+            d += 1;
+        }
+        if let Some(degree) = f.degree().filter(|&degree| degree > 0) {
+            factors.push((f, degree));
+        }
+        factors
+    }
+
+    // For a monic product of distinct irreducible polynomials of degree d: those polynomials
+    fn equal_degree_factors(&self, d: usize) -> Vec<Polynomial> {
+        let n = self.degree().unwrap_or(0);
+        if n == 0 {
+            return vec![];
+        }
+        if n == d {
+            return vec![self.clone()];
+        }
+        let p = self.field.modulus();
+        let one = Self::one(&self.field);
+        let exponent = (p.pow(d as u32) - buint(1)) >> 1;
+        // Candidates a are all the non-constant polynomials of degree < n, in order: the digits
+        // of the counter in base p are their coefficients. Some a splits f, by the CRT.
+        let mut counter = p.clone();
+        loop {
+            let a = Self::from_counter(&counter, &self.field);
+            counter += buint(1);
+            let candidate = if p == &buint(2) {
+                // The trace a + a^2 + a^4 + ... + a^(2^(d - 1)) is 0 or 1 mod each factor
+                let mut power = a.divmod(self).unwrap().1;
+                let mut trace = power.clone();
+                for _ in 1..d {
+                    power = (power.clone() * power).divmod(self).unwrap().1;
+                    trace = trace + power.clone();
+                }
+                trace
+            } else {
+                a.pow_mod(&exponent, self).unwrap() - one.clone()
+            };
+            let g = self.gcd(&candidate).unwrap();
+            if g.degree().is_some_and(|degree| degree > 0 && degree < n) {
+                let mut factors = g.equal_degree_factors(d);
+                factors.extend(self.divmod(&g).unwrap().0.equal_degree_factors(d));
+                return factors;
+            }
+        }
+    }
+
+    // The polynomial whose coefficients are the base-p digits of n
+    fn from_counter(n: &BigUint, field: &Arc<Field>) -> Self {
+        let p = field.modulus();
+        let mut n = n.clone();
+        let mut coefficients = vec![];
+        while n > buint(0) {
+            coefficients.push(Element::in_field(bint(&n % p), field));
+            n /= p;
+        }
+        Self::from_trusted(coefficients, field)
+    }
+}
+
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (mod {})",
+            self.format_with("x"),
+            self.field.modulus()
+        )
+    }
+}
+
+impl fmt::Debug for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Polynomial")
+            .field("value", &self.format_with("x"))
+            .field("modulus", self.field.modulus())
+            .finish()
+    }
+}
+
+impl ops::Neg for Polynomial {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        let minus_one = Element::in_field(-1, &self.field);
+        self.scale(&minus_one)
+    }
+}
+
+impl ops::Add for Polynomial {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.checked_add(&other).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl ops::Sub for Polynomial {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.checked_sub(&other).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl ops::Mul for Polynomial {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        self.checked_mul(&other).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use num_bigint::BigUint;
+    use proptest::prelude::*;
+
+    use crate::element::{Element, Field};
+    use crate::polynomial::Polynomial;
+    use crate::util::buint;
+    use crate::ECCError;
+
+    fn f7() -> Arc<Field> {
+        Field::new(7u32)
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let field = f7();
+        let a = Polynomial::new(&[1, 2, 3], &field);
+        let b = Polynomial::new(&[6, 1], &field);
+        // (3x^2 + 2x + 1)(x - 1) = 3x^3 - x^2 - x - 1
+        assert_eq!(
+            a.clone() * b.clone(),
+            Polynomial::new(&[-1, -1, -1, 3], &field)
+        );
+        assert_eq!(a.clone() + b.clone(), Polynomial::new(&[0, 3, 3], &field));
+        assert_eq!(a.clone() - a.clone(), Polynomial::zero(&field));
+        assert_eq!(-b.clone() + b.clone(), Polynomial::zero(&field));
+        assert_eq!(a.degree(), Some(2));
+        assert_eq!(Polynomial::zero(&field).degree(), None);
+        // Leading zeros are dropped
+        assert_eq!(Polynomial::new(&[1, 0, 7], &field).degree(), Some(0));
+
+        assert_eq!(a.evaluate(&Element::new(2, 7)), Element::new(17, 7));
+        assert_eq!(a.derivative(), Polynomial::new(&[2, 6], &field));
+        // x^7 has a zero derivative over F_7
+        assert!(Polynomial::new(&[0, 0, 0, 0, 0, 0, 0, 1], &field)
+            .derivative()
+            .is_zero());
+
+        assert_eq!(a.to_string(), "3x^2 + 2x + 1 (mod 7)");
+        assert_eq!(Polynomial::zero(&field).to_string(), "0 (mod 7)");
+        assert_eq!(b.format_with("t"), "t + 6");
+    }
+
+    #[test]
+    fn test_errors() {
+        let field = f7();
+        let a = Polynomial::new(&[1, 2, 3], &field);
+        let other = Polynomial::new(&[1, 2, 3], &Field::new(11u32));
+        assert!(matches!(
+            a.checked_add(&other),
+            Err(ECCError::ModulusMismatch)
+        ));
+        assert!(matches!(a.gcd(&other), Err(ECCError::ModulusMismatch)));
+        assert!(matches!(
+            Polynomial::from_elements(vec![Element::new(1, 11)], &field),
+            Err(ECCError::ModulusMismatch)
+        ));
+        assert!(matches!(
+            a.divmod(&Polynomial::zero(&field)),
+            Err(ECCError::NotInvertible)
+        ));
+        let points = [
+            (Element::new(1, 7), Element::new(2, 7)),
+            (Element::new(8, 7), Element::new(3, 7)),
+        ];
+        assert!(matches!(
+            Polynomial::interpolate(&points, &field),
+            Err(ECCError::NotInvertible)
+        ));
+    }
+
+    #[test]
+    fn test_interpolate() {
+        // Shamir's secret sharing: the secret is f(0), any 3 shares of a degree 2 polynomial
+        // recover it
+        let field = Field::new(8191u32);
+        let f = Polynomial::new(&[1234, 166, 94], &field);
+        let shares: Vec<(Element, Element)> = (1..=5)
+            .map(|i| {
+                let x = Element::in_field(i, &field);
+                (x.clone(), f.evaluate(&x))
+            })
+            .collect();
+        let recovered = Polynomial::interpolate(&shares[2..], &field).unwrap();
+        assert_eq!(recovered, f);
+        assert_eq!(
+            recovered.evaluate(&Element::in_field(0, &field)).value(),
+            buint(1234)
+        );
+        assert!(Polynomial::interpolate(&[], &field).unwrap().is_zero());
+    }
+
+    #[test]
+    fn test_gcd_and_pow_mod() {
+        let field = f7();
+        // (x - 1)(x - 2) and (x - 2)(x - 3)
+        let a = Polynomial::new(&[2, -3, 1], &field);
+        let b = Polynomial::new(&[6, -5, 1], &field);
+        assert_eq!(a.gcd(&b).unwrap(), Polynomial::new(&[-2, 1], &field));
+        // The GCD is monic
+        let scaled = a.clone() * Polynomial::new(&[3], &field);
+        assert_eq!(scaled.gcd(&b).unwrap(), Polynomial::new(&[-2, 1], &field));
+
+        // x^7 = x mod any polynomial, over F_7
+        let x = Polynomial::x(&field);
+        assert_eq!(x.pow_mod(&buint(7), &a).unwrap(), x);
+        assert_eq!(
+            x.pow_mod(&buint(0), &a).unwrap(),
+            Polynomial::new(&[1], &field)
+        );
+    }
+
+    #[test]
+    fn test_roots() {
+        let field = f7();
+        // (x - 2)(x - 5)(x^2 + 1): x^2 + 1 has no root, since 7 = 3 (mod 4)
+        let f = Polynomial::new(&[3, 0, 4, 0, 1], &field);
+        assert_eq!(f.roots(), vec![Element::new(2, 7), Element::new(5, 7)]);
+        assert!(Polynomial::new(&[1, 0, 1], &field).roots().is_empty());
+        // Repeated roots appear once
+        let square = Polynomial::new(&[-3, 1], &field) * Polynomial::new(&[-3, 1], &field);
+        assert_eq!(square.roots(), vec![Element::new(3, 7)]);
+        // Over F_2
+        let f2 = Field::new(2u32);
+        assert_eq!(
+            Polynomial::new(&[0, 1, 1], &f2).roots(),
+            vec![Element::new(0, 2), Element::new(1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_factor() {
+        let field = Field::new(3u32);
+        // t^4 + 1 = (t^2 + t + 2)(t^2 + 2t + 2) over F_3
+        let f = Polynomial::new(&[1, 0, 0, 0, 1], &field);
+        assert_eq!(
+            f.factor(),
+            vec![
+                (Polynomial::new(&[2, 1, 1], &field), 1),
+                (Polynomial::new(&[2, 2, 1], &field), 1),
+            ]
+        );
+        // 2(x + 1)^3.(x^2 + 1)^2: the cube is a polynomial in x^3
+        let (linear, quadratic) = (
+            Polynomial::new(&[1, 1], &field),
+            Polynomial::new(&[1, 0, 1], &field),
+        );
+        let g = Polynomial::new(&[2], &field)
+            * linear.clone()
+            * linear.clone()
+            * linear.clone()
+            * quadratic.clone()
+            * quadratic.clone();
+        assert_eq!(
+            g.factor(),
+            vec![
+                (Polynomial::new(&[1, 1], &field), 3),
+                (Polynomial::new(&[1, 0, 1], &field), 2),
+            ]
+        );
+        assert!(Polynomial::new(&[5], &field).factor().is_empty());
+        assert!(Polynomial::zero(&field).factor().is_empty());
+    }
+
+    #[test]
+    fn test_is_irreducible() {
+        // -1 is a square mod 13: x^2 + 1 = (x - 5)(x + 5)
+        let f13 = Field::new(13u32);
+        assert!(!Polynomial::new(&[1, 0, 1], &f13).is_irreducible());
+        // No roots, but not irreducible either
+        assert!(!Polynomial::new(&[1, 0, 0, 0, 1], &Field::new(3u32)).is_irreducible());
+        assert!(Polynomial::new(&[-2, 0, 0, 1], &f7()).is_irreducible());
+        assert!(Polynomial::new(&[1, 1, 0, 0, 1], &Field::new(2u32)).is_irreducible());
+        assert!(!Polynomial::new(&[3], &f7()).is_irreducible());
+        assert!(!Polynomial::zero(&f7()).is_irreducible());
+    }
+
+    fn polynomial(coefficients: &[u32]) -> Polynomial {
+        Polynomial::new(coefficients, &Field::new(101u32))
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn test_divmod(
+            a in proptest::collection::vec(0u32..101, 0..12),
+            b in proptest::collection::vec(0u32..101, 1..6),
+        ) {
+            let (a, b) = (polynomial(&a), polynomial(&b));
+            prop_assume!(!b.is_zero());
+            let (q, r) = a.divmod(&b).unwrap();
+            prop_assert_eq!(q * b.clone() + r.clone(), a);
+            prop_assert!(r.degree() < b.degree());
+        }
+
+        #[test]
+        fn test_gcd_divides_both(
+            a in proptest::collection::vec(0u32..101, 1..8),
+            b in proptest::collection::vec(0u32..101, 1..8),
+            c in proptest::collection::vec(0u32..101, 1..4),
+        ) {
+            let (a, b, c) = (polynomial(&a), polynomial(&b), polynomial(&c));
+            prop_assume!(!c.is_zero());
+            let (a, b) = (a * c.clone(), b * c.clone());
+            let g = a.gcd(&b).unwrap();
+            prop_assert!(a.divmod(&g).unwrap().1.is_zero());
+            prop_assert!(b.divmod(&g).unwrap().1.is_zero());
+            // c divides both, so it divides their GCD
+            prop_assert!(g.divmod(&c).unwrap().1.is_zero());
+        }
+
+        #[test]
+        fn test_interpolate_recovers_polynomial(
+            coefficients in proptest::collection::vec(0u32..101, 0..10),
+        ) {
+            let f = polynomial(&coefficients);
+            let field = f.field().clone();
+            let points: Vec<(Element, Element)> = (0..coefficients.len())
+                .map(|i| {
+                    let x = Element::in_field(i * 7 + 3, &field);
+                    (x.clone(), f.evaluate(&x))
+                })
+                .collect();
+            prop_assert_eq!(Polynomial::interpolate(&points, &field).unwrap(), f);
+        }
+
+        #[test]
+        fn test_factor_and_roots(coefficients in proptest::collection::vec(0u32..101, 2..9)) {
+            let f = polynomial(&coefficients);
+            prop_assume!(f.degree().unwrap_or(0) > 0);
+            let factors = f.factor();
+
+            // The product of the factors gives back f, up to its leading coefficient
+            let mut product = Polynomial::constant(f.leading_coefficient().unwrap().clone());
+            for (factor, multiplicity) in &factors {
+                prop_assert!(factor.is_irreducible());
+                prop_assert_eq!(factor.leading_coefficient().unwrap().value(), BigUint::from(1u32));
+                for _ in 0..*multiplicity {
+                    product = product * factor.clone();
+                }
+            }
+            prop_assert_eq!(product, f.clone());
+
+            // Roots are the factors of degree 1
+            let mut roots: Vec<Element> = factors
+                .iter()
+                .filter(|(factor, _)| factor.degree() == Some(1))
+                .map(|(factor, _)| -factor.coefficients()[0].clone())
+                .collect();
+            roots.sort_by_key(|root| root.value());
+            prop_assert_eq!(f.roots(), roots.clone());
+            for root in &roots {
+                prop_assert!(f.evaluate(root).is_zero());
+            }
+        }
+    }
+}
@@ -0,0 +1,376 @@
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::msm::{pippenger, shamir};
+use crate::util::{bint, buint};
+use crate::{Curve, ECCError, Point};
+
+/// BIP-340 tagged hash: SHA256(SHA256(tag) || SHA256(tag) || data)
+pub fn tagged_hash(tag: &str, data: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.update(tag_hash);
+    hasher.update(tag_hash);
+    for chunk in data {
+        hasher.update(chunk);
+    }
+    hasher.finalize().into()
+}
+
+/// Schnorr signatures over secp256k1, as specified in BIP-340.
+///
+/// Public keys are x-only (32 bytes): the matching point is the one with an even y-coordinate.
+/// Signatures are 64 bytes: the x-coordinate of R, followed by s.
+#[derive(Debug, Clone)]
+pub struct Schnorr {
+    curve: Curve,
+    generator: Point,
+    order: BigUint,
+}
+
+impl Schnorr {
+    /// Errors with `CurveMismatch` unless `curve` is secp256k1 (y^2 = x^3 + 7 mod 2^256 - 2^32 - 977),
+    /// with the standard generator. Points and keys live on `curve`, whatever its name.
+    pub fn new(curve: &Curve) -> Result<Self, ECCError> {
+        let secp = Curve::named("secp256k1")?;
+        if curve.p != secp.p || curve.a2 != secp.a2 || curve.a4 != secp.a4 || curve.a6 != secp.a6 {
+            return Err(ECCError::CurveMismatch);
+        }
+        let (generator, order) = match (curve.generator(), curve.subgroup_order()) {
+            (Some(generator), Some(order)) => (generator, order.clone()),
+            _ => return Err(ECCError::CurveMismatch),
+        };
+        let standard = secp.generator().unwrap();
+        if (&generator.x, &generator.y) != (&standard.x, &standard.y)
+            || Some(&order) != secp.subgroup_order()
+        {
+            return Err(ECCError::CurveMismatch);
+        }
+        Ok(Self {
+            curve: curve.clone(),
+            generator,
+            order,
+        })
+    }
+
+    pub fn public_key(&self, secret_key: &[u8; 32]) -> Result<[u8; 32], ECCError> {
+        let d = self.secret_scalar(secret_key)?;
+        let p = self.generator.mul_ct(&d, &self.order)?;
+        Ok(to_bytes32(&p.x.value()))
+    }
+
+    pub fn sign(
+        &self,
+        message: &[u8],
+        secret_key: &[u8; 32],
+        aux_rand: &[u8; 32],
+    ) -> Result<[u8; 64], ECCError> {
+        let n = &self.order;
+        let d_prime = self.secret_scalar(secret_key)?;
+        let p = self.generator.mul_ct(&d_prime, n)?;
+        // Use the secret key whose public point has an even y-coordinate
+        let d = if has_even_y(&p) { d_prime } else { n - d_prime };
+        let p_bytes = to_bytes32(&p.x.value());
+
+        // Mask the secret key with the auxiliary randomness before deriving the nonce
+        let aux_hash = tagged_hash("BIP0340/aux", &[aux_rand]);
+        let mut t = to_bytes32(&d);
+        for (t_byte, aux_byte) in t.iter_mut().zip(aux_hash.iter()) {
+            *t_byte ^= aux_byte;
+        }
+        let rand = tagged_hash("BIP0340/nonce", &[&t, &p_bytes, message]);
+        let k_prime = BigUint::from_bytes_be(&rand) % n;
+        if k_prime == buint(0) {
+            return Err(ECCError::InvalidScalar);
+        }
+        let r = self.generator.mul_ct(&k_prime, n)?;
+        let k = if has_even_y(&r) { k_prime } else { n - k_prime };
+        let r_bytes = to_bytes32(&r.x.value());
+
+        let e = self.challenge(&r_bytes, &p_bytes, message);
+        let s = (k + e * d) % n;
+
+        let mut signature = [0u8; 64];
+        signature[..32].copy_from_slice(&r_bytes);
+        signature[32..].copy_from_slice(&to_bytes32(&s));
+
+        // BIP-340 recommends verifying the signature before returning it, to catch computation errors
+        if !self.verify(message, &p_bytes, &signature) {
+            return Err(ECCError::InvalidSignature);
+        }
+        Ok(signature)
+    }
+
+    pub fn verify(&self, message: &[u8], public_key: &[u8; 32], signature: &[u8; 64]) -> bool {
+        let (p, r, s) = match self.parse(public_key, signature) {
+            Some(parsed) => parsed,
+            None => return false,
+        };
+        let e = self.challenge(&signature[..32], public_key, message);
+
+        // R = s.G - e.P
+        let point = match shamir(&s, &self.generator, &(&self.order - e), &p) {
+            Ok(point) => point,
+            Err(_) => return false,
+        };
+        !point.is_at_infinity() && has_even_y(&point) && point.x.value() == r
+    }
+
+    /// Batch verification, as described in BIP-340. Checks that
+    ///     (s1 + a2.s2 + ... + au.su).G = R1 + a2.R2 + ... + au.Ru + e1.P1 + (a2.e2).P2 + ... + (au.eu).Pu
+    /// where a1 = 1 and the other coefficients are pseudo-random. As the BIP suggests, we derive them
+    /// from a hash of all inputs: a forger can't predict them without fixing the whole batch first.
+    pub fn verify_batch(&self, batch: &[(&[u8], [u8; 32], [u8; 64])]) -> bool {
+        // An empty batch is trivially valid
+        if batch.is_empty() {
+            return true;
+        }
+        let n = &self.order;
+
+        let mut seed = Sha256::new();
+        for (message, public_key, signature) in batch {
+            seed.update(public_key);
+            seed.update(message);
+            seed.update(signature);
+        }
+        let seed: [u8; 32] = seed.finalize().into();
+
+        let mut s_sum = buint(0);
+        let mut points = vec![];
+        let mut scalars = vec![];
+        for (i, (message, public_key, signature)) in batch.iter().enumerate() {
+            let (p, r, s) = match self.parse(public_key, signature) {
+                Some(parsed) => parsed,
+                None => return false,
+            };
+            let r_point = match lift_x(&self.curve, &r) {
+                Some(point) => point,
+                None => return false,
+            };
+            let e = self.challenge(&signature[..32], public_key, message);
+
+            let a = if i == 0 {
+                buint(1)
+            } else {
+                let a = tagged_hash("BIP0340/batch", &[&seed, &(i as u64).to_be_bytes()]);
+                BigUint::from_bytes_be(&a) % n
+            };
+            s_sum = (s_sum + a.clone() * s) % n;
+            points.extend([r_point, p]);
+            scalars.extend([a.clone(), (a * e) % n]);
+        }
+        // The right-hand side is a multi-scalar multiplication: that's where batching pays off
+        match pippenger(&points, &scalars) {
+            Ok(rhs) => self.generator.mul(s_sum) == rhs,
+            Err(_) => false,
+        }
+    }
+
+    // Parses a public key and signature into (P, r, s), checking that:
+    //   - the public key is the x-coordinate of a point on the curve
+    //   - r < p and s < n
+    fn parse(
+        &self,
+        public_key: &[u8; 32],
+        signature: &[u8; 64],
+    ) -> Option<(Point, BigUint, BigUint)> {
+        let p = lift_x(&self.curve, &BigUint::from_bytes_be(public_key))?;
+        let r = BigUint::from_bytes_be(&signature[..32]);
+        let s = BigUint::from_bytes_be(&signature[32..]);
+        if r >= self.curve.p || s >= self.order {
+            return None;
+        }
+        Some((p, r, s))
+    }
+
+    // e = int(hash_BIP0340/challenge(bytes(R) || bytes(P) || m)) mod n
+    fn challenge(&self, r: &[u8], p: &[u8], message: &[u8]) -> BigUint {
+        BigUint::from_bytes_be(&tagged_hash("BIP0340/challenge", &[r, p, message])) % &self.order
+    }
+
+    // Secret keys must be in [1, n-1]
+    fn secret_scalar(&self, secret_key: &[u8; 32]) -> Result<BigUint, ECCError> {
+        let d = BigUint::from_bytes_be(secret_key);
+        if d == buint(0) || d >= self.order {
+            return Err(ECCError::InvalidScalar);
+        }
+        Ok(d)
+    }
+}
+
+/// Returns the point with x-coordinate `x` and an even y-coordinate, if there is one.
+fn lift_x(curve: &Curve, x: &BigUint) -> Option<Point> {
+    if x >= &curve.p {
+        return None;
+    }
+    let x = curve.element(bint(x.clone()));
+    let c = x.pow(3) + curve.a6.clone();
+    let y = c.sqrt()?;
+    let y = if y.value().bit(0) { -y } else { y };
+    Some(curve.point(bint(x.value()), bint(y.value())))
+}
+
+fn has_even_y(point: &Point) -> bool {
+    !point.y.value().bit(0)
+}
+
+fn to_bytes32(value: &BigUint) -> [u8; 32] {
+    let bytes = value.to_bytes_be();
+    let mut out = [0u8; 32];
+    out[32 - bytes.len()..].copy_from_slice(&bytes);
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use crate::schnorr::Schnorr;
+    use crate::util::{bint, buint};
+    use crate::{Curve, ECCError};
+
+    // The official BIP-340 test vectors, from https://github.com/bitcoin/bips/blob/master/bip-0340/test-vectors.csv
+    const TEST_VECTORS: &str = include_str!("../fixtures/bip340_test_vectors.csv");
+
+    struct TestVector {
+        index: usize,
+        secret_key: Option<[u8; 32]>,
+        public_key: Vec<u8>,
+        aux_rand: Option<[u8; 32]>,
+        message: Vec<u8>,
+        signature: [u8; 64],
+        valid: bool,
+    }
+
+    fn decode(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn test_vectors() -> Vec<TestVector> {
+        TEST_VECTORS
+            .lines()
+            .skip(1)
+            .map(|line| {
+                let fields: Vec<&str> = line.split(',').collect();
+                TestVector {
+                    index: fields[0].parse().unwrap(),
+                    secret_key: (!fields[1].is_empty())
+                        .then(|| decode(fields[1]).try_into().unwrap()),
+                    public_key: decode(fields[2]),
+                    aux_rand: (!fields[3].is_empty())
+                        .then(|| decode(fields[3]).try_into().unwrap()),
+                    message: decode(fields[4]),
+                    signature: decode(fields[5]).try_into().unwrap(),
+                    valid: fields[6] == "TRUE",
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bip340_vectors() {
+        let curve = Curve::named("secp256k1").unwrap();
+        let schnorr = Schnorr::new(&curve).unwrap();
+        let vectors = test_vectors();
+        assert_eq!(vectors.len(), 19);
+
+        for v in vectors {
+            if let (Some(secret_key), Some(aux_rand)) = (v.secret_key, v.aux_rand) {
+                let public_key = schnorr.public_key(&secret_key).unwrap();
+                assert_eq!(public_key.to_vec(), v.public_key, "vector {}", v.index);
+                let signature = schnorr.sign(&v.message, &secret_key, &aux_rand).unwrap();
+                assert_eq!(signature, v.signature, "vector {}", v.index);
+            }
+
+            let valid = match v.public_key.clone().try_into() {
+                Ok(public_key) => schnorr.verify(&v.message, &public_key, &v.signature),
+                Err(_) => false,
+            };
+            assert_eq!(valid, v.valid, "vector {}", v.index);
+        }
+    }
+
+    #[test]
+    fn test_batch_verification() {
+        let curve = Curve::named("secp256k1").unwrap();
+        let schnorr = Schnorr::new(&curve).unwrap();
+        let vectors = test_vectors();
+
+        let valid: Vec<(&[u8], [u8; 32], [u8; 64])> = vectors
+            .iter()
+            .filter(|v| v.valid)
+            .map(|v| {
+                (
+                    v.message.as_slice(),
+                    v.public_key.clone().try_into().unwrap(),
+                    v.signature,
+                )
+            })
+            .collect();
+        assert!(schnorr.verify_batch(&valid));
+        assert!(schnorr.verify_batch(&[]));
+
+        // A single invalid signature makes the whole batch fail
+        for invalid in vectors.iter().filter(|v| !v.valid) {
+            let public_key = invalid.public_key.clone().try_into().unwrap();
+            let batch = [
+                valid[0],
+                (invalid.message.as_slice(), public_key, invalid.signature),
+            ];
+            assert!(!schnorr.verify_batch(&batch), "vector {}", invalid.index);
+        }
+    }
+
+    #[test]
+    fn test_schnorr_requires_secp256k1() {
+        let curve = Curve::new("trivial curve", 0, 3, 8, 13);
+        assert!(matches!(Schnorr::new(&curve), Err(ECCError::CurveMismatch)));
+
+        // Same equation, but no generator, or another one
+        let secp = Curve::named("secp256k1").unwrap();
+        let bare = Curve::new("k1", bint(0), bint(0), bint(7), bint(secp.p.clone()));
+        assert!(matches!(Schnorr::new(&bare), Err(ECCError::CurveMismatch)));
+        let order = secp.subgroup_order().unwrap().clone();
+        let g2 = secp.generator().unwrap().mul(buint(2));
+        let other = bare
+            .with_generator(
+                bint(g2.x.value()),
+                bint(g2.y.value()),
+                order.clone(),
+                buint(1),
+            )
+            .unwrap();
+        assert!(matches!(Schnorr::new(&other), Err(ECCError::CurveMismatch)));
+
+        // A renamed secp256k1 works, and its points stay on it
+        let g = secp.generator().unwrap();
+        let renamed = bare
+            .with_generator(bint(g.x.value()), bint(g.y.value()), order, buint(1))
+            .unwrap();
+        let schnorr = Schnorr::new(&renamed).unwrap();
+        assert_eq!(schnorr.generator.curve, renamed);
+        let secret_key = [7u8; 32];
+        assert_eq!(
+            schnorr.public_key(&secret_key).unwrap(),
+            Schnorr::new(&secp)
+                .unwrap()
+                .public_key(&secret_key)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid_secret_keys() {
+        let curve = Curve::named("secp256k1").unwrap();
+        let schnorr = Schnorr::new(&curve).unwrap();
+        assert!(matches!(
+            schnorr.sign(b"hello", &[0u8; 32], &[0u8; 32]),
+            Err(ECCError::InvalidScalar)
+        ));
+        assert!(matches!(
+            schnorr.public_key(&[0xffu8; 32]),
+            Err(ECCError::InvalidScalar)
+        ));
+    }
+}
@@ -1,5 +1,8 @@
 use num_bigint::{BigInt, BigUint, ToBigInt, ToBigUint};
 
+use crate::element::Element;
+use crate::Point;
+
 // I'm tired of writing x.to_biguint().unwrap()...
 // .............. ...and x.to_bigint().unwrap()...
 
@@ -10,3 +13,61 @@ pub fn buint<T: ToBigUint>(val: T) -> BigUint {
 pub fn bint<T: ToBigInt>(val: T) -> BigInt {
     val.to_bigint().unwrap()
 }
+
+pub fn gcd(a: &BigUint, b: &BigUint) -> BigUint {
+    let (mut a, mut b) = (a.clone(), b.clone());
+    while b != buint(0) {
+        let r = &a % &b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+pub fn lcm(a: &BigUint, b: &BigUint) -> BigUint {
+    a / gcd(a, b) * b
+}
+
+/// Factors n by trial division, as (prime, exponent) pairs: 24 -> [(2, 3), (3, 1)].
+/// Only meant for the small numbers we get on toy curves.
+pub fn factor(n: &BigUint) -> Vec<(BigUint, u32)> {
+    let mut n = n.clone();
+    let mut factors = vec![];
+    let mut d = buint(2);
+    while &d * &d <= n {
+        let mut exponent = 0;
+        while &n % &d == buint(0) {
+            n /= &d;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((d.clone(), exponent));
+        }
+        d += buint(1);
+    }
+    if n > buint(1) {
+        factors.push((n, 1));
+    }
+    factors
+}
+
+// Chinese Remainder Theorem for two congruences with coprime moduli:
+// x = a1 (mod m1), x = a2 (mod m2)  =>  x = a1 + m1 * ((a2 - a1) / m1 mod m2)  (mod m1.m2)
+pub fn crt(first: &(BigUint, BigUint), second: &(BigUint, BigUint)) -> (BigUint, BigUint) {
+    let (a1, m1) = first;
+    let (a2, m2) = second;
+    if m2 == &buint(1) {
+        return first.clone();
+    }
+    if m1 == &buint(1) {
+        return second.clone();
+    }
+    let diff = Element::new(bint(a2 % m2) - bint(a1 % m2), m2.clone());
+    let step = diff / Element::new(bint(m1.clone()), m2.clone());
+    (a1 + m1 * step.value(), m1 * m2)
+}
+
+/// Hashable representation of a point, for lookup tables
+pub fn point_key(point: &Point) -> (BigUint, BigUint) {
+    (point.x.value(), point.y.value())
+}